@@ -0,0 +1,112 @@
+use clap::Subcommand;
+use prisma_config::config::Config;
+use prisma_core::{
+    extension::{ExtensionProvider, ExtensionType},
+    STORE_CACHE_DIR,
+};
+use prisma_providers::{Cache, Client};
+
+pub mod add;
+pub mod apply;
+pub mod check;
+pub mod gc;
+pub mod generations;
+pub mod init;
+pub mod plan;
+pub mod remove;
+pub mod rollback;
+pub mod run;
+pub mod sync;
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Declare a plugin or mod in the config
+    Add(add::AddArgs),
+    /// Create a starter config and the store layout in the current directory
+    Init(init::InitArgs),
+    /// Check the config for mistakes
+    Check(check::CheckArgs),
+    /// Resolve, download and link everything declared in the config
+    Sync(sync::SyncArgs),
+    /// Show what sync would change and save it as a plan
+    Plan(plan::PlanArgs),
+    /// Execute a saved plan
+    Apply(apply::ApplyArgs),
+    /// Start the server from the deployed core
+    Run(run::RunArgs),
+    /// Drop a plugin or mod from the config and unlink it
+    Remove(remove::RemoveArgs),
+    /// Delete files of the shared store that no instance uses
+    Gc(gc::GcArgs),
+    /// Inspect deployment generations
+    Generations(generations::GenerationsArgs),
+    /// Switch back to an earlier generation
+    Rollback(rollback::RollbackArgs),
+}
+
+impl Command {
+    pub async fn run(self) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        match self {
+            Command::Add(args) => add::run(args).await,
+            Command::Init(args) => init::run(args).await,
+            Command::Check(args) => check::run(args).await,
+            Command::Sync(args) => sync::run(args).await,
+            Command::Plan(args) => plan::run(args).await,
+            Command::Apply(args) => apply::run(args).await,
+            Command::Run(args) => run::run(args).await,
+            Command::Remove(args) => remove::run(args).await,
+            Command::Gc(args) => gc::run(args).await,
+            Command::Generations(args) => generations::run(args).await,
+            Command::Rollback(args) => rollback::run(args).await,
+        }
+    }
+}
+
+/// Prints config diagnostics, fails if any of them is an error
+pub fn validate(config: &Config) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let diagnostics = config.validate();
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    match diagnostics.iter().filter(|x| x.severity.is_error()).count() {
+        0 => Ok(()),
+        errors => Err(format!("config has {} error(s)", errors).into()),
+    }
+}
+
+/// Mod or plugin as asked by `--plugin` and `--mod`, or as the core platform takes them
+pub async fn extension_type(
+    plugin: bool,
+    r#mod: bool,
+    provider: ExtensionProvider,
+    config: Option<&String>,
+) -> Result<ExtensionType, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    if plugin {
+        return Ok(ExtensionType::Plugin(provider));
+    }
+    if r#mod {
+        return Ok(ExtensionType::Mod(provider));
+    }
+    let config = Config::parse_config(config).await?;
+    Ok(match config.core.platform.supports_mods() {
+        true => ExtensionType::Mod(provider),
+        false => ExtensionType::Plugin(provider),
+    })
+}
+
+/// Builds the shared HTTP client of the providers from the network settings
+///
+/// Provider answers are cached in [STORE_CACHE_DIR], `refresh` revalidates all of them.
+/// `offline` is added to the config option.
+pub fn connect(
+    config: &Config,
+    refresh: bool,
+    offline: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let cache = Cache::new(STORE_CACHE_DIR).with_refresh(refresh);
+    let client = Client::new(&config.network)?
+        .with_cache(Some(cache))
+        .with_offline(offline || config.network.offline);
+    Client::set_global(client);
+    Ok(())
+}
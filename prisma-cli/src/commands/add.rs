@@ -0,0 +1,53 @@
+use clap::Args;
+use prisma_config::{config::ExtensionConfig, edit};
+use prisma_core::{extension::ExtensionProvider, platform::Platform, version::Version};
+
+#[derive(Debug, Args)]
+pub struct AddArgs {
+    /// Extension name (project slug or id)
+    pub name: String,
+    /// Add as a plugin
+    #[arg(long, conflicts_with = "mod")]
+    pub plugin: bool,
+    /// Add as a mod
+    #[arg(long = "mod")]
+    pub r#mod: bool,
+    /// Where to download it from
+    #[arg(long, default_value_t = ExtensionProvider::default())]
+    pub provider: ExtensionProvider,
+    /// Platform of the extension, inherited from the core if not set
+    #[arg(long)]
+    pub platform: Option<Platform>,
+    /// Minecraft version, inherited from the core if not set
+    #[arg(short, long)]
+    pub game_version: Option<String>,
+    /// Path to the config file
+    #[arg(short, long)]
+    pub config: Option<String>,
+}
+
+/// Appends an extension to the config without touching the rest of the file
+pub async fn run(args: AddArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let provider =
+        super::extension_type(args.plugin, args.r#mod, args.provider, args.config.as_ref()).await?;
+
+    let extension = ExtensionConfig {
+        name: args.name,
+        platform: args.platform,
+        provider,
+        version: Version {
+            game_version: args.game_version,
+            ..Default::default()
+        },
+        options: Default::default(),
+    };
+
+    edit::add_extension(args.config.as_ref(), &extension).await?;
+
+    println!(
+        "Added {} {}",
+        extension.provider.as_ref().to_lowercase(),
+        extension.name
+    );
+    Ok(())
+}
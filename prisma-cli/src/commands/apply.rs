@@ -0,0 +1,75 @@
+use clap::Args;
+use prisma_config::{config::Config, ConfigError};
+use prisma_core::{provider::Provider, LOCK_PATH, PLAN_PATH, STORE_PATH};
+use prisma_store::{
+    generation::Generations,
+    lock::Lock,
+    plan::{Action, Plan},
+    store::{create_layout, Store},
+};
+
+use crate::progress::ProgressBars;
+
+#[derive(Debug, Args)]
+pub struct ApplyArgs {
+    /// Path to a plan saved by `prisma plan`
+    #[arg(short, long, default_value = PLAN_PATH)]
+    pub plan: String,
+    /// Path to the config file, only its network and generation settings are used
+    #[arg(short, long)]
+    pub config: Option<String>,
+    /// Don't touch the network, files must be in the store already
+    #[arg(long)]
+    pub offline: bool,
+}
+
+/// Executes a saved plan without resolving anything again
+pub async fn run(
+    args: ApplyArgs,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let plan = Plan::load(&args.plan).await?;
+    super::plan::print(&plan);
+    if plan.is_empty() {
+        return Ok(());
+    }
+
+    let config = match Config::parse_config(args.config).await {
+        Ok(config) => config,
+        Err(ConfigError::NotFound { .. }) => Config::default(),
+        Err(e) => return Err(e.into()),
+    };
+    super::connect(&config, false, args.offline)?;
+
+    let platform = plan
+        .changes
+        .iter()
+        .find_map(|x| match &x.item.provider {
+            Provider::Core(platform) => Some(platform.clone()),
+            Provider::Extension(_) => None,
+        })
+        .unwrap_or_default();
+    create_layout(&platform).await?;
+
+    let mut store = Store::load_or_default(STORE_PATH).await?;
+    let progress = ProgressBars::show();
+    store.apply(&plan).await?;
+    drop(progress);
+    let items = plan
+        .changes
+        .iter()
+        .filter(|x| x.action != Action::Remove)
+        .map(|x| x.item.clone())
+        .collect::<Vec<_>>();
+    let lock = Lock::from_store(&store, &items).await?;
+    lock.save(LOCK_PATH).await?;
+    let generation = Generations::default()
+        .commit(&store, &lock, config.generations.keep)
+        .await?;
+    tokio::fs::remove_file(&args.plan).await?;
+
+    match generation {
+        Some(generation) => println!("Applied, generation {}", generation.number),
+        None => println!("Applied"),
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use clap::Args;
+use prisma_config::config::Config;
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    /// Path to the config file
+    #[arg(short, long)]
+    pub config: Option<String>,
+    /// Also check that the server port is free
+    #[arg(long)]
+    pub port: bool,
+}
+
+/// Parses and validates the config without touching the store
+pub async fn run(
+    args: CheckArgs,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let config = Config::parse_config(args.config).await?;
+    super::validate(&config)?;
+    if let Some(diagnostic) = args.port.then(|| config.check_port()).flatten() {
+        eprintln!("{}", diagnostic);
+    }
+    println!("Config is valid");
+    Ok(())
+}
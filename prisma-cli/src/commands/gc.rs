@@ -0,0 +1,23 @@
+use clap::Args;
+use prisma_store::shared::SharedStore;
+
+#[derive(Debug, Args)]
+pub struct GcArgs {
+    /// Show what would be deleted without deleting it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Deletes files of the shared store that no instance on this host uses
+pub async fn run(args: GcArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let shared = SharedStore::global();
+    let removed = match args.dry_run {
+        true => shared.garbage().await?,
+        false => shared.collect_garbage().await?,
+    };
+    for path in &removed {
+        println!("{:<16} {}", "deleted", path.display());
+    }
+    println!("{} file(s) in {}", removed.len(), shared.dir().display());
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use clap::{Args, Subcommand};
+use prisma_core::time::now;
+use prisma_store::generation::Generations;
+
+#[derive(Debug, Args)]
+pub struct GenerationsArgs {
+    #[command(subcommand)]
+    pub command: GenerationsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GenerationsCommand {
+    /// Show every generation, the active one is marked with `*`
+    List,
+}
+
+/// Shows the deployment generations
+pub async fn run(
+    args: GenerationsArgs,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    match args.command {
+        GenerationsCommand::List => list().await,
+    }
+}
+
+async fn list() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let generations = Generations::default();
+    let current = generations.current().await;
+    let list = generations.list().await?;
+    if list.is_empty() {
+        println!("No generations yet, run sync or apply first");
+    }
+    for generation in list {
+        let marker = if Some(generation.number) == current {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{} {:>4}  {:<12} {} item(s)",
+            marker,
+            generation.number,
+            describe_age(generation.created_at),
+            generation.items.len()
+        );
+        for item in &generation.items {
+            println!("         {}", item);
+        }
+    }
+    Ok(())
+}
+
+/// Age of a unix timestamp, like `3h ago`
+fn describe_age(created_at: u64) -> String {
+    let age = now().saturating_sub(created_at);
+    match age {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", age / 60),
+        3600..86400 => format!("{}h ago", age / 3600),
+        _ => format!("{}d ago", age / 86400),
+    }
+}
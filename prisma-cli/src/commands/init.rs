@@ -0,0 +1,70 @@
+use clap::Args;
+use prisma_config::config::{Config, CoreConfig, CoreOptions};
+use prisma_core::{platform::Platform, version::Version, CONFIG_PATH};
+use prisma_store::store::create_layout;
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Server platform (Vanilla, Paper, Fabric, ...)
+    #[arg(short, long, default_value_t = Platform::default())]
+    pub platform: Platform,
+    /// Minecraft version, latest if not set
+    #[arg(short, long)]
+    pub game_version: Option<String>,
+    /// Mod loader version for loader platforms, latest if not set
+    #[arg(long)]
+    pub loader_version: Option<String>,
+    /// Minimum RAM allocation (in MB)
+    #[arg(long)]
+    pub min_memory: Option<u32>,
+    /// Maximum RAM allocation (in MB)
+    #[arg(long)]
+    pub max_memory: Option<u32>,
+    /// Server port
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Where to write the config, the format is chosen by extension
+    #[arg(short, long, default_value = CONFIG_PATH)]
+    pub config: String,
+    /// Overwrite an existing config
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+/// Writes a starter config and creates the store layout
+pub async fn run(args: InitArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    if !args.force {
+        let existing = if tokio::fs::try_exists(&args.config).await? {
+            Some(args.config.clone())
+        } else {
+            Config::locate(None::<String>).await.ok()
+        };
+        if let Some(existing) = existing {
+            return Err(format!("{} already exists, use --force to overwrite", existing).into());
+        }
+    }
+
+    let defaults = CoreOptions::default();
+    let config = Config::new()
+        .with_core(CoreConfig {
+            platform: args.platform.clone(),
+            version: Version {
+                game_version: args.game_version,
+                loader_version: args.loader_version,
+                ..Default::default()
+            },
+            options: Default::default(),
+        })
+        .with_options(CoreOptions {
+            port: args.port.unwrap_or(defaults.port),
+            min_memory: args.min_memory.unwrap_or(defaults.min_memory),
+            max_memory: args.max_memory.unwrap_or(defaults.max_memory),
+            ..defaults
+        });
+
+    create_layout(&args.platform).await?;
+    config.save_config(Some(&args.config)).await?;
+
+    println!("Created {} for {}", args.config, args.platform);
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use clap::Args;
+use prisma_config::config::Config;
+use prisma_core::{version::Version, PLAN_PATH, STORE_PATH};
+use prisma_store::{
+    plan::{Action, Plan, Resolved},
+    store::Store,
+};
+
+#[derive(Debug, Args)]
+pub struct PlanArgs {
+    /// Path to the config file
+    #[arg(short, long)]
+    pub config: Option<String>,
+    /// Ask providers again instead of using cached answers
+    #[arg(long, conflicts_with = "offline")]
+    pub refresh: bool,
+    /// Don't touch the network, resolve from cached answers and the store
+    #[arg(long)]
+    pub offline: bool,
+    /// Where to save the plan
+    #[arg(short, long, default_value = PLAN_PATH)]
+    pub out: String,
+    /// Print the plan as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Builds a plan, prints it and saves it for `apply`
+pub async fn run(args: PlanArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let config = Config::parse_config(args.config).await?;
+    super::validate(&config)?;
+    super::connect(&config, args.refresh, args.offline)?;
+    let store = Store::load_or_default(STORE_PATH).await?;
+
+    let plan = Plan::build(&config, &store).await?;
+    plan.save(&args.out).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+    } else {
+        print(&plan);
+        println!("Saved to {}", args.out);
+    }
+    Ok(())
+}
+
+/// Prints a plan one change per line
+pub fn print(plan: &Plan) {
+    for change in &plan.changes {
+        let sign = match change.action {
+            Action::Add => "+",
+            Action::Upgrade | Action::Downgrade | Action::Reinstall => "~",
+            Action::Remove => "-",
+            Action::Unchanged => " ",
+        };
+        let versions = match (&change.old, &change.new) {
+            (Some(_), Some(new)) if change.action.is_unchanged() => describe(new),
+            (Some(old), Some(new)) => format!("{} -> {}", describe(old), describe(new)),
+            (None, Some(new)) => describe(new),
+            (Some(old), None) => describe(old),
+            (None, None) => "-".to_string(),
+        };
+        println!(
+            "{} {:<10} {:<32} {}",
+            sign,
+            change.action,
+            change.item.provider.describe(),
+            versions
+        );
+    }
+    if plan.is_empty() {
+        println!("Nothing to do");
+    }
+}
+
+fn describe(resolved: &Resolved) -> String {
+    Version {
+        game_version: resolved.game_version.clone(),
+        version_build: resolved.version_build.clone(),
+        loader_version: resolved.loader_version.clone(),
+        ..Default::default()
+    }
+    .describe()
+}
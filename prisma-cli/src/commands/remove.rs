@@ -0,0 +1,73 @@
+use clap::Args;
+use prisma_config::{config::Config, edit};
+use prisma_core::{extension::ExtensionProvider, LOCK_PATH, STORE_PATH};
+use prisma_store::{generation::Generations, lock::Lock, store::Store, StoreError};
+
+#[derive(Debug, Args)]
+pub struct RemoveArgs {
+    /// Extension name
+    pub name: String,
+    /// Remove a plugin
+    #[arg(long, conflicts_with = "mod")]
+    pub plugin: bool,
+    /// Remove a mod
+    #[arg(long = "mod")]
+    pub r#mod: bool,
+    /// Path to the config file
+    #[arg(short, long)]
+    pub config: Option<String>,
+}
+
+/// Removes an extension from the config, its symbolic link from the server and its
+/// entry from the lockfile
+pub async fn run(
+    args: RemoveArgs,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let kind = super::extension_type(
+        args.plugin,
+        args.r#mod,
+        ExtensionProvider::default(),
+        args.config.as_ref(),
+    )
+    .await?;
+    let removed = edit::remove_extension(args.config.as_ref(), &args.name, &kind)
+        .await?
+        .ok_or_else(|| format!("{} {} is not declared", kind.as_ref(), args.name))?;
+
+    let mut store = Store::load_or_default(STORE_PATH).await?;
+    let unlinked = store
+        .remove_extension(&removed.name, &removed.provider)
+        .await?;
+    if let Some(item) = &unlinked {
+        println!("Unlinked {}", item.symbol_link);
+        store.save(STORE_PATH).await?;
+    }
+
+    // without it the next `sync --locked` would install the extension again
+    let lock = match Lock::load(LOCK_PATH).await {
+        Ok(mut lock) => {
+            lock.remove_extension(&removed.name, &removed.provider);
+            lock.save(LOCK_PATH).await?;
+            Some(lock)
+        }
+        Err(StoreError::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    if let (Some(_), Some(lock)) = (unlinked, lock) {
+        let config = Config::parse_config(args.config).await?;
+        let generation = Generations::default()
+            .commit(&store, &lock, config.generations.keep)
+            .await?;
+        if let Some(generation) = generation {
+            println!("Generation {}", generation.number);
+        }
+    }
+
+    println!(
+        "Removed {} {}",
+        removed.provider.as_ref().to_lowercase(),
+        removed.name
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use clap::Args;
+use prisma_core::{LOCK_PATH, STORE_PATH};
+use prisma_store::generation::Generations;
+
+#[derive(Debug, Args)]
+pub struct RollbackArgs {
+    /// Generation to activate, the one before the active generation by default
+    pub generation: Option<u32>,
+}
+
+/// Switches the deployed files, the store and the lockfile to another generation
+pub async fn run(
+    args: RollbackArgs,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let generations = Generations::default();
+    let number = match args.generation {
+        Some(number) => number,
+        None => generations
+            .previous()
+            .await?
+            .ok_or("No generation to roll back to")?,
+    };
+
+    let generation = generations.rollback(number, STORE_PATH, LOCK_PATH).await?;
+    for item in &generation.items {
+        println!("{:<16} {}", "deployed", item);
+    }
+    println!("Rolled back to generation {}", generation.number);
+    Ok(())
+}
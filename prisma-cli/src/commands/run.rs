@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use clap::Args;
+use prisma_config::config::Config;
+use prisma_core::STORE_PATH;
+use prisma_server::{
+    launcher::Launcher,
+    supervisor::{Supervisor, SupervisorEvent},
+};
+use prisma_store::store::Store;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Path to the config file
+    #[arg(short, long)]
+    pub config: Option<String>,
+    /// Java executable
+    #[arg(long, default_value = "java")]
+    pub java: String,
+    /// Restart the server on crash and stop it gracefully on SIGTERM
+    #[arg(long)]
+    pub supervise: bool,
+    /// Seconds to wait after `stop` before killing the server
+    #[arg(long, default_value_t = 60, requires = "supervise")]
+    pub stop_timeout: u64,
+    /// Crashes in a row before giving up
+    #[arg(long, default_value_t = 5, requires = "supervise")]
+    pub max_restarts: u32,
+}
+
+/// Starts the deployed core attached to the console
+pub async fn run(args: RunArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let config = Config::parse_config(args.config).await?;
+    let store = Store::load(STORE_PATH).await?;
+
+    let launcher = Launcher::from_store(&store, config.options)?.with_java(args.java);
+    println!("Starting {} {}", launcher.java, launcher.args().join(" "));
+    let result = if args.supervise {
+        let (events, mut received) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(event) = received.recv().await {
+                print_event(&event);
+            }
+        });
+        Supervisor::new(launcher)
+            .with_stop_timeout(Duration::from_secs(args.stop_timeout))
+            .with_max_restarts(args.max_restarts)
+            .with_events(events)
+            .run()
+            .await
+    } else {
+        launcher.run().await.map_err(Into::into)
+    };
+
+    // stdin reader may still block the runtime, exit right away with the server code
+    match result {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Prints a restart or kill of the supervised server
+fn print_event(event: &SupervisorEvent) {
+    match event {
+        SupervisorEvent::Restarting {
+            status,
+            delay,
+            restarts,
+            max_restarts,
+        } => eprintln!(
+            "Server exited with {}, restarting in {}s ({}/{})",
+            status,
+            delay.as_secs(),
+            restarts,
+            max_restarts
+        ),
+        SupervisorEvent::Killed { timeout } => {
+            eprintln!("Server didn't stop in {}s, killing it", timeout.as_secs())
+        }
+    }
+}
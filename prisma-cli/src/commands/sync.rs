@@ -0,0 +1,146 @@
+use clap::Args;
+use prisma_config::config::Config;
+use prisma_core::{item::Item, platform::Platform};
+use prisma_core::{LOCK_PATH, STORE_PATH};
+use prisma_server::{launcher::write_user_jvm_args, properties::sync_properties};
+use prisma_store::{
+    generation::Generations,
+    lock::Lock,
+    store::{create_layout, Store},
+};
+
+use crate::progress::ProgressBars;
+
+#[derive(Debug, Args)]
+pub struct SyncArgs {
+    /// Path to the config file
+    #[arg(short, long)]
+    pub config: Option<String>,
+    /// Ask providers again instead of using cached answers
+    #[arg(long, conflicts_with = "offline")]
+    pub refresh: bool,
+    /// Don't touch the network, resolve from cached answers and the store
+    #[arg(long)]
+    pub offline: bool,
+    /// Install exactly what prisma.lock records, without resolving anything
+    #[arg(long)]
+    pub locked: bool,
+}
+
+/// Loads the config, installs every missing item and repairs broken ones
+pub async fn run(args: SyncArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let config = Config::parse_config(args.config).await?;
+    super::validate(&config)?;
+    super::connect(&config, args.refresh, args.offline)?;
+    let config = config.normolise();
+
+    let items = config.items();
+    let lock = match args.locked {
+        true => {
+            let lock = Lock::load(LOCK_PATH)
+                .await
+                .map_err(|e| format!("{e}, run sync without --locked to write {LOCK_PATH}"))?;
+            lock.check(&items)?;
+            Some(lock)
+        }
+        false => None,
+    };
+
+    create_layout(&config.core.platform).await?;
+    let mut store = Store::load_or_default(STORE_PATH).await?;
+    let progress = ProgressBars::show();
+
+    // Fix what is already installed
+    let repaired = store.validate().await?;
+    let failed = store.repair().await?;
+
+    let installed = match &lock {
+        // Install what differs from the lock
+        Some(lock) => store.install_locked(&items, lock).await?,
+        // Install what is missing or isn't the configured version anymore
+        None => {
+            let outdated = items
+                .iter()
+                .filter(|item| !is_current(&store, item))
+                .collect::<Vec<_>>();
+            store.fill_new(outdated).await?
+        }
+    };
+    drop(progress);
+
+    for item in &items {
+        let status = if installed.contains(&item.provider) {
+            "installed"
+        } else if failed.iter().any(|x| x.item.provider == item.provider) {
+            "failed to repair"
+        } else if repaired.iter().any(|x| x.item.provider == item.provider) {
+            "repaired"
+        } else if !is_current(&store, item) {
+            // only frozen items are left as they are
+            "frozen"
+        } else {
+            "up to date"
+        };
+        let version = store
+            .find(&item.provider)
+            .map(|x| x.item.version.describe())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<16} {:<32} {}",
+            status,
+            item.provider.describe(),
+            version
+        );
+    }
+
+    if failed.is_empty() {
+        let lock = match lock {
+            Some(lock) => lock,
+            None => {
+                let lock = Lock::from_store(&store, &items).await?;
+                lock.save(LOCK_PATH).await?;
+                lock
+            }
+        };
+        let generation = Generations::default()
+            .commit(&store, &lock, config.generations.keep)
+            .await?;
+        if let Some(generation) = generation {
+            println!("{:<16} {}", "generation", generation.number);
+        }
+    }
+
+    if matches!(config.core.platform, Platform::Forge | Platform::NeoForge) {
+        write_user_jvm_args(&config.options).await?;
+    }
+
+    let properties = sync_properties(&config.options).await?;
+    for change in &properties.changed {
+        println!(
+            "{:<16} {:<32} {} -> {}",
+            "property",
+            change.key,
+            change.old.as_deref().unwrap_or("-"),
+            change.new
+        );
+    }
+    for drift in &properties.drift {
+        eprintln!(
+            "warning: {} was edited by hand ({:?}, expected {:?}), overwritten by the config",
+            drift.key, drift.found, drift.expected
+        );
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} item(s) could not be repaired", failed.len()).into())
+    }
+}
+
+/// Item is installed with every version pinned in the config
+fn is_current(store: &Store, item: &Item) -> bool {
+    store
+        .find(&item.provider)
+        .is_some_and(|x| x.item.version.satisfies(&item.version))
+}
@@ -1,3 +1,18 @@
-fn main() {
-    println!("Hello, world!");
+use clap::Parser;
+use commands::Command;
+
+mod commands;
+mod progress;
+
+/// A modern Minecraft server bundler
+#[derive(Debug, Parser)]
+#[command(name = "prisma", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    Cli::parse().command.run().await
 }
@@ -0,0 +1,80 @@
+//! Progress bars of the downloads made by a command.
+use std::collections::HashMap;
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use prisma_store::progress::{subscribe, Progress};
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+
+/// Draws a bar per download on stderr until dropped
+///
+/// Nothing is drawn when stderr isn't a terminal.
+pub struct ProgressBars {
+    bars: MultiProgress,
+    task: JoinHandle<()>,
+}
+
+impl ProgressBars {
+    pub fn show() -> Self {
+        let bars = MultiProgress::new();
+        let mut receiver = subscribe();
+        let task = tokio::spawn({
+            let bars = bars.clone();
+            async move {
+                let mut downloads = HashMap::new();
+                loop {
+                    match receiver.recv().await {
+                        Ok(progress) => update(&bars, &mut downloads, progress),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            }
+        });
+        Self { bars, task }
+    }
+}
+
+impl Drop for ProgressBars {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = self.bars.clear();
+    }
+}
+
+/// Applies an event to the bar of its download
+fn update(bars: &MultiProgress, downloads: &mut HashMap<String, ProgressBar>, progress: Progress) {
+    match progress {
+        Progress::Started { item, url, total } => {
+            let bar = match total {
+                Some(total) => ProgressBar::new(total).with_style(
+                    ProgressStyle::with_template(
+                        "{msg:32} [{bar:30}] {bytes}/{total_bytes} {bytes_per_sec}",
+                    )
+                    .unwrap_or_else(|_| ProgressStyle::default_bar())
+                    .progress_chars("=> "),
+                ),
+                None => ProgressBar::new_spinner().with_style(
+                    ProgressStyle::with_template("{msg:32} {spinner} {bytes} {bytes_per_sec}")
+                        .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+                ),
+            };
+            downloads.insert(url, bars.add(bar.with_message(item)));
+        }
+        Progress::Advanced { url, done, .. } => {
+            if let Some(bar) = downloads.get(&url) {
+                bar.set_position(done);
+            }
+        }
+        Progress::Finished { url, .. } => {
+            if let Some(bar) = downloads.remove(&url) {
+                bar.finish_and_clear();
+                bars.remove(&bar);
+            }
+        }
+        Progress::Failed { url, .. } => {
+            if let Some(bar) = downloads.remove(&url) {
+                bar.abandon();
+            }
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use prisma_core::{
     extension::ExtensionType, item::Item, options::Options, platform::Platform, provider::Name,
-    version::Version, CONFIG_PATH,
+    version::Version, CONFIG_PATH, CONFIG_PATHS,
 };
-use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
 
+pub use crate::generations::GenerationOptions;
+pub use crate::network::{Endpoint, NetworkOptions};
+pub use crate::properties::{Difficulty, Gamemode, ServerProperties};
+use crate::{format::ConfigFormat, ConfigError};
+
 /// Configuration for a Minecraft server instance
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -14,6 +18,12 @@ pub struct Config {
     pub extensions: Vec<ExtensionConfig>,
     /// Server core options
     pub options: CoreOptions,
+    /// HTTP client settings
+    #[serde(default)]
+    pub network: NetworkOptions,
+    /// Retention of deployment generations
+    #[serde(default)]
+    pub generations: GenerationOptions,
 }
 
 impl Config {
@@ -43,36 +53,65 @@ impl Config {
         self.update_version().update_platform()
     }
 
-    /// Parses a TOML configuration file into a Config struct
+    /// Converts core and every extension into [Item]s, core first
+    pub fn items(&self) -> Vec<Item> {
+        std::iter::once(Item::from(self.core.clone()))
+            .chain(self.extensions.iter().cloned().map(Item::from))
+            .collect()
+    }
+
+    /// Finds the config file
+    ///
+    /// Returns `path` if it is set, otherwise the first existing file of [CONFIG_PATHS].
+    pub async fn locate(path: Option<impl ToString>) -> Result<String, ConfigError> {
+        if let Some(path) = path {
+            return Ok(path.to_string());
+        }
+        for candidate in CONFIG_PATHS {
+            if tokio::fs::try_exists(candidate)
+                .await
+                .map_err(ConfigError::io(candidate))?
+            {
+                return Ok(candidate.to_string());
+            }
+        }
+        Err(ConfigError::NotFound {
+            candidates: CONFIG_PATHS.iter().map(|x| x.to_string()).collect(),
+        })
+    }
+
+    /// Parses a configuration file into a Config struct, the format is chosen by extension
     ///
     /// # Arguments
     ///
-    /// * `path` - Optional path to the config file. If not provided, uses [DEFAULT_CONFIG_PATH]
+    /// * `path` - Optional path to the config file. If not provided, searches [CONFIG_PATHS]
     ///
     /// # Returns
     ///
-    /// * Result<[Config], Box<[dyn std::error::Error]>> - The parsed config or an error
-    pub async fn parse_config(
-        path: Option<impl ToString>,
-    ) -> Result<Config, Box<dyn std::error::Error>> {
-        let path =
-            tokio::fs::read_to_string(path.map_or(CONFIG_PATH.to_string(), |p| p.to_string()))
-                .await?;
-        Ok(ron::de::from_bytes(&tokio::fs::read(path).await?)?)
-    }
-
-    pub async fn save_config(
-        &self,
-        path: Option<impl ToString>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let path =
-            tokio::fs::read_to_string(path.map_or(CONFIG_PATH.to_string(), |p| p.to_string()))
-                .await?;
-        tokio::fs::write(
-            path,
-            ron::ser::to_string_pretty(&self, PrettyConfig::default().enumerate_arrays(true))?,
-        )
-        .await?;
+    /// * Result<[Config], [ConfigError]> - The parsed config or an error
+    pub async fn parse_config(path: Option<impl ToString>) -> Result<Config, ConfigError> {
+        let path = Self::locate(path).await?;
+        let format = ConfigFormat::from_path(&path)?;
+        let text = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(ConfigError::io(&path))?;
+        format.parse(&text, &path)
+    }
+
+    /// Saves the config, the format is chosen by extension
+    ///
+    /// * `path` - Optional path to the config file. If not provided, overwrites the existing
+    ///   config or creates [CONFIG_PATH]
+    pub async fn save_config(&self, path: Option<impl ToString>) -> Result<(), ConfigError> {
+        let path = match Self::locate(path).await {
+            Ok(path) => path,
+            Err(ConfigError::NotFound { .. }) => CONFIG_PATH.to_string(),
+            Err(e) => return Err(e),
+        };
+        let format = ConfigFormat::from_path(&path)?;
+        tokio::fs::write(&path, format.serialize(self)?)
+            .await
+            .map_err(ConfigError::io(&path))?;
         Ok(())
     }
 }
@@ -148,47 +187,6 @@ pub struct CoreOptions {
     pub properties: ServerProperties,
 }
 
-/// Server properties for configuring Minecraft server behavior
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct ServerProperties {
-    /// Server MOTD
-    pub motd: Option<String>,
-    /// Max players
-    pub max_players: Option<u32>,
-    /// Online mode
-    pub online_mode: Option<bool>,
-    /// Difficulty
-    pub difficulty: Option<Difficulty>,
-    /// Gamemode
-    pub gamemode: Option<Gamemode>,
-    /// View distance
-    pub view_distance: Option<u32>,
-    /// Allow nether
-    pub allow_nether: Option<bool>,
-    /// Enable command blocks
-    pub enable_command_block: Option<bool>,
-}
-
-/// Server difficulty levels
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Difficulty {
-    Peaceful,
-    Easy,
-    Normal,
-    Hard,
-}
-
-/// Server game modes
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Gamemode {
-    Survival,
-    Creative,
-    Adventure,
-    Spectator,
-}
-
 impl Default for CoreOptions {
     fn default() -> Self {
         Self {
@@ -201,6 +199,15 @@ impl Default for CoreOptions {
     }
 }
 
+impl CoreOptions {
+    /// Keys of `server.properties` managed by the config, `server-port` included
+    pub fn server_properties(&self) -> Vec<(String, String)> {
+        std::iter::once(("server-port".to_string(), self.port.to_string()))
+            .chain(self.properties.entries())
+            .collect()
+    }
+}
+
 impl Config {
     /// Creates a new default Config instance
     pub fn new() -> Self {
@@ -224,4 +231,16 @@ impl Config {
         self.options = options;
         self
     }
+
+    /// Sets the HTTP client settings
+    pub fn with_network(mut self, network: NetworkOptions) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Sets the retention of deployment generations
+    pub fn with_generations(mut self, generations: GenerationOptions) -> Self {
+        self.generations = generations;
+        self
+    }
 }
@@ -0,0 +1,480 @@
+//! Format preserving edits of a RON config.
+//!
+//! [Config::save_config](crate::config::Config::save_config) re-serializes the whole file,
+//! which drops comments and ordering. [ConfigDocument] instead splices entries directly
+//! into the source text of the `extensions` list and leaves everything else untouched.
+use prisma_core::extension::ExtensionType;
+use ron::ser::PrettyConfig;
+
+use crate::{
+    config::{Config, ExtensionConfig},
+    format::ConfigFormat,
+    ConfigError,
+};
+
+/// Raw text of a config file that can be edited in place
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDocument {
+    source: String,
+}
+
+/// Position of a single entry inside a list
+#[derive(Debug, Clone, Copy)]
+struct Element {
+    /// First byte of the value
+    start: usize,
+    /// End of the value, trailing whitespace excluded
+    end: usize,
+    /// End of the trailing comma, if there is one
+    comma_end: Option<usize>,
+}
+
+/// Position of the `extensions` list
+#[derive(Debug)]
+struct List {
+    /// Byte of the `[`
+    open: usize,
+    /// Byte of the `]`
+    close: usize,
+    /// Indentation of the line with the `extensions` key
+    key_indent: String,
+    elements: Vec<Element>,
+}
+
+impl ConfigDocument {
+    /// Wraps config source text
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+
+    /// Reads a RON config file
+    ///
+    /// * `path` - Optional path to the config file. If not provided, searches [CONFIG_PATHS](prisma_core::CONFIG_PATHS)
+    pub async fn load(path: Option<impl ToString>) -> Result<Self, ConfigError> {
+        let path = Config::locate(path).await?;
+        let format = ConfigFormat::from_path(&path)?;
+        if !format.is_ron() {
+            return Err(ConfigError::NotEditable {
+                path: path.into(),
+                format,
+            });
+        }
+        Ok(Self::new(
+            tokio::fs::read_to_string(&path)
+                .await
+                .map_err(ConfigError::io(&path))?,
+        ))
+    }
+
+    /// Writes the edited text back
+    pub async fn save(&self, path: Option<impl ToString>) -> Result<(), ConfigError> {
+        let path = Config::locate(path).await?;
+        tokio::fs::write(&path, &self.source)
+            .await
+            .map_err(ConfigError::io(&path))?;
+        Ok(())
+    }
+
+    /// Current text of the document
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Parses every entry of the `extensions` list
+    pub fn extensions(&self) -> Result<Vec<ExtensionConfig>, ConfigError> {
+        let list = self.find_extensions()?;
+        list.elements
+            .iter()
+            .map(|e| {
+                ron::from_str(&self.source[e.start..e.end]).map_err(|x| ConfigError::Structure {
+                    reason: x.to_string(),
+                    position: e.start,
+                })
+            })
+            .collect()
+    }
+
+    /// Appends an extension to the end of the `extensions` list
+    ///
+    /// Returns an error if an extension with the same name already exists.
+    pub fn add_extension(&mut self, extension: &ExtensionConfig) -> Result<(), ConfigError> {
+        if self.extensions()?.iter().any(|e| e.name == extension.name) {
+            return Err(ConfigError::DuplicateExtension {
+                name: extension.name.clone(),
+            });
+        }
+
+        let list = self.find_extensions()?;
+        let entry =
+            ron::ser::to_string_pretty(extension, PrettyConfig::default()).map_err(|e| {
+                ConfigError::Serialize {
+                    format: ConfigFormat::Ron,
+                    message: e.to_string(),
+                }
+            })?;
+
+        match list.elements.last() {
+            Some(last) => {
+                let indent = line_indent(&self.source, last.start);
+                let entry = reindent(&entry, &indent);
+                match last.comma_end {
+                    Some(comma_end) => {
+                        self.source
+                            .insert_str(comma_end, &format!("\n{}{},", indent, entry));
+                    }
+                    None => {
+                        self.source
+                            .insert_str(last.end, &format!(",\n{}{},", indent, entry));
+                    }
+                }
+            }
+            None => {
+                let indent = format!("{}    ", list.key_indent);
+                let entry = reindent(&entry, &indent);
+                // keep comments inside an empty list, drop only the trailing whitespace
+                let from = self.source[list.open + 1..list.close].trim_end().len() + list.open + 1;
+                self.source.replace_range(
+                    from..list.close,
+                    &format!("\n{}{},\n{}", indent, entry, list.key_indent),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the mod or plugin `name`, like `kind`, together with the comments attached to it
+    ///
+    /// Returns the removed extension, or `None` if it wasn't declared.
+    pub fn remove_extension(
+        &mut self,
+        name: &str,
+        kind: &ExtensionType,
+    ) -> Result<Option<ExtensionConfig>, ConfigError> {
+        let list = self.find_extensions()?;
+        let extensions = self.extensions()?;
+        let Some((element, extension)) = list
+            .elements
+            .iter()
+            .zip(extensions)
+            .find(|(_, e)| is_extension(e, name, kind))
+        else {
+            return Ok(None);
+        };
+
+        let bytes = self.source.as_bytes();
+        let line_start = self.source[..element.start]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let own_line =
+            skip_trivia(&self.source[line_start..element.start], 0) == element.start - line_start;
+        let mut end = element.comma_end.unwrap_or(element.end);
+
+        let start = if own_line {
+            // take the rest of the line, including a trailing line comment
+            let rest = &self.source[end..];
+            let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            let tail = rest[..line_end].trim();
+            if tail.is_empty() || tail.starts_with("//") {
+                end += line_end;
+            }
+            // take full line comments right above the entry
+            let mut start = line_start;
+            while start > 0 {
+                let prev_start = self.source[..start - 1].rfind('\n').map_or(0, |i| i + 1);
+                if self.source[prev_start..start]
+                    .trim_start()
+                    .starts_with("//")
+                {
+                    start = prev_start;
+                } else {
+                    break;
+                }
+            }
+            start
+        } else {
+            while end < bytes.len() && matches!(bytes[end], b' ' | b'\t') {
+                end += 1;
+            }
+            element.start
+        };
+
+        self.source.replace_range(start..end, "");
+        Ok(Some(extension))
+    }
+
+    /// Locates the `extensions` list of the root struct
+    fn find_extensions(&self) -> Result<List, ConfigError> {
+        let s = self.source.as_str();
+        let bytes = s.as_bytes();
+
+        // optional struct name before the root `(`
+        let mut i = skip_trivia(s, 0);
+        i = skip_ident(s, i);
+        i = skip_trivia(s, i);
+        if bytes.get(i) != Some(&b'(') {
+            return Err(structure("config root must be a struct", i));
+        }
+        i += 1;
+
+        loop {
+            i = skip_trivia(s, i);
+            match bytes.get(i) {
+                Some(b')') | None => return Err(structure("config has no `extensions` field", i)),
+                _ => {}
+            }
+            let key_start = i;
+            i = skip_ident(s, i);
+            let key = &s[key_start..i];
+            i = skip_trivia(s, i);
+            if bytes.get(i) != Some(&b':') {
+                return Err(structure(format!("expected `:` after `{}`", key), i));
+            }
+            i = skip_trivia(s, i + 1);
+
+            if key == "extensions" {
+                if bytes.get(i) != Some(&b'[') {
+                    return Err(structure("`extensions` must be a list", i));
+                }
+                let open = i;
+                let mut elements = Vec::new();
+                i += 1;
+                loop {
+                    i = skip_trivia(s, i);
+                    match bytes.get(i) {
+                        Some(b']') => break,
+                        None => return Err(structure("unclosed `extensions` list", i)),
+                        _ => {}
+                    }
+                    let start = i;
+                    i = skip_value(s, i);
+                    let end = start + s[start..i].trim_end().len();
+                    let comma_end = if bytes.get(i) == Some(&b',') {
+                        i += 1;
+                        Some(i)
+                    } else {
+                        None
+                    };
+                    elements.push(Element {
+                        start,
+                        end,
+                        comma_end,
+                    });
+                }
+                return Ok(List {
+                    open,
+                    close: i,
+                    key_indent: line_indent(s, key_start),
+                    elements,
+                });
+            }
+
+            i = skip_value(s, i);
+            if bytes.get(i) == Some(&b',') {
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Adds an extension to the config file
+///
+/// RON is edited in place, other formats are parsed and written back, losing comments.
+pub async fn add_extension(
+    path: Option<impl ToString>,
+    extension: &ExtensionConfig,
+) -> Result<(), ConfigError> {
+    let path = Config::locate(path).await?;
+    match ConfigDocument::load(Some(&path)).await {
+        Ok(mut document) => {
+            document.add_extension(extension)?;
+            document.save(Some(&path)).await
+        }
+        Err(ConfigError::NotEditable { .. }) => {
+            let config = Config::parse_config(Some(&path)).await?;
+            if config.extensions.iter().any(|e| e.name == extension.name) {
+                return Err(ConfigError::DuplicateExtension {
+                    name: extension.name.clone(),
+                });
+            }
+            config
+                .add_extension(extension.clone())
+                .save_config(Some(&path))
+                .await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Removes the mod or plugin `name`, like `kind`, from the config file
+///
+/// RON is edited in place, other formats are parsed and written back, losing comments.
+/// Returns the removed extension, or `None` if it wasn't declared.
+pub async fn remove_extension(
+    path: Option<impl ToString>,
+    name: &str,
+    kind: &ExtensionType,
+) -> Result<Option<ExtensionConfig>, ConfigError> {
+    let path = Config::locate(path).await?;
+    match ConfigDocument::load(Some(&path)).await {
+        Ok(mut document) => {
+            let removed = document.remove_extension(name, kind)?;
+            if removed.is_some() {
+                document.save(Some(&path)).await?;
+            }
+            Ok(removed)
+        }
+        Err(ConfigError::NotEditable { .. }) => {
+            let mut config = Config::parse_config(Some(&path)).await?;
+            let Some(index) = config
+                .extensions
+                .iter()
+                .position(|e| is_extension(e, name, kind))
+            else {
+                return Ok(None);
+            };
+            let removed = config.extensions.remove(index);
+            config.save_config(Some(&path)).await?;
+            Ok(Some(removed))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Extension is the mod or plugin `name`, the provider of `kind` isn't compared
+fn is_extension(extension: &ExtensionConfig, name: &str, kind: &ExtensionType) -> bool {
+    extension.name == name && extension.provider.as_ref() == kind.as_ref()
+}
+
+/// Structure error at the given byte
+fn structure(reason: impl Into<String>, position: usize) -> ConfigError {
+    ConfigError::Structure {
+        reason: reason.into(),
+        position,
+    }
+}
+
+/// Skips whitespace and comments
+fn skip_trivia(s: &str, mut i: usize) -> usize {
+    let bytes = s.as_bytes();
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\t' | b'\r' | b'\n' => i += 1,
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = s[i..].find('\n').map_or(bytes.len(), |n| i + n + 1);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(s, i),
+            _ => break,
+        }
+    }
+    i
+}
+
+/// Skips a (possibly nested) block comment starting at `i`
+fn skip_block_comment(s: &str, mut i: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut depth = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            depth += 1;
+            i += 2;
+        } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                break;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    i
+}
+
+/// Skips an identifier
+fn skip_ident(s: &str, mut i: usize) -> usize {
+    let bytes = s.as_bytes();
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+        i += 1;
+    }
+    i
+}
+
+/// Skips a string or char literal starting at `i`
+fn skip_string(s: &str, mut i: usize) -> usize {
+    let bytes = s.as_bytes();
+    let quote = bytes[i];
+    i += 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+/// Skips a raw string `r#"..."#` starting at `i`
+fn skip_raw_string(s: &str, i: usize) -> usize {
+    let hashes = s[i + 1..].bytes().take_while(|b| *b == b'#').count();
+    let body = i + 1 + hashes + 1;
+    let terminator = format!("\"{}", "#".repeat(hashes));
+    s[body..]
+        .find(&terminator)
+        .map_or(s.len(), |n| body + n + terminator.len())
+}
+
+/// Skips one value, stopping at the `,` or closing bracket that ends it
+fn skip_value(s: &str, mut i: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' => i = skip_string(s, i),
+            b'r' if matches!(bytes.get(i + 1), Some(b'"' | b'#'))
+                && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric()) =>
+            {
+                i = skip_raw_string(s, i)
+            }
+            b'/' if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => i = skip_trivia(s, i),
+            b'(' | b'[' | b'{' => {
+                depth += 1;
+                i += 1;
+            }
+            b')' | b']' | b'}' if depth == 0 => break,
+            b')' | b']' | b'}' => {
+                depth -= 1;
+                i += 1;
+            }
+            b',' if depth == 0 => break,
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+/// Leading whitespace of the line containing `pos`
+fn line_indent(s: &str, pos: usize) -> String {
+    let line_start = s[..pos].rfind('\n').map_or(0, |i| i + 1);
+    s[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
+/// Indents every line but the first
+fn reindent(text: &str, indent: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(n, line)| {
+            if n == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
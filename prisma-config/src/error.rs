@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use crate::format::ConfigFormat;
+
+/// Errors of reading, writing and editing the config
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// Config file couldn't be read or written
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Config file isn't valid
+    #[error("{}:{line}:{column}: {message}", path.display())]
+    Parse {
+        path: PathBuf,
+        format: ConfigFormat,
+        /// 1-based line, 0 if unknown
+        line: usize,
+        /// 1-based column, 0 if unknown
+        column: usize,
+        message: String,
+    },
+    /// Config couldn't be serialized
+    #[error("can't write config as {format}: {message}")]
+    Serialize {
+        format: ConfigFormat,
+        message: String,
+    },
+    /// File extension isn't one of the supported formats
+    #[error("{}: unknown config format, expected .ron, .toml, .json or .yaml", path.display())]
+    UnknownFormat { path: PathBuf },
+    /// Format can't be edited without rewriting the whole file
+    #[error("{}: {format} configs can't be edited in place", path.display())]
+    NotEditable { path: PathBuf, format: ConfigFormat },
+    /// No config file in any of the default locations
+    #[error("config not found, tried {}", candidates.join(", "))]
+    NotFound { candidates: Vec<String> },
+    /// Config text has an unexpected structure, `position` is a byte offset
+    #[error("invalid config at byte {position}: {reason}")]
+    Structure { reason: String, position: usize },
+    /// Extension with the same name is already declared
+    #[error("extension {name} is already declared")]
+    DuplicateExtension { name: String },
+}
+
+impl ConfigError {
+    /// Maps an IO error of the given file
+    pub(crate) fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { path, source }
+    }
+}
@@ -0,0 +1,123 @@
+//! Config file formats, chosen by file extension.
+use std::path::Path;
+
+use ron::ser::PrettyConfig;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::ConfigError;
+
+/// Supported config file formats
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    strum::Display,
+    strum::AsRefStr,
+    strum::EnumIter,
+    strum::EnumIs,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum ConfigFormat {
+    #[default]
+    Ron,
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Detects the format by file extension
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        match path
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("ron") => Ok(ConfigFormat::Ron),
+            Some("toml") => Ok(ConfigFormat::Toml),
+            Some("json") => Ok(ConfigFormat::Json),
+            Some("yaml" | "yml") => Ok(ConfigFormat::Yaml),
+            _ => Err(ConfigError::UnknownFormat { path: path.into() }),
+        }
+    }
+
+    /// Parses text in this format
+    ///
+    /// `path` is only used in the error.
+    pub fn parse<T: DeserializeOwned>(
+        &self,
+        text: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<T, ConfigError> {
+        let error = |line: usize, column: usize, message: String| ConfigError::Parse {
+            path: path.as_ref().into(),
+            format: *self,
+            line,
+            column,
+            message,
+        };
+        match self {
+            ConfigFormat::Ron => ron::from_str(text)
+                .map_err(|e| error(e.position.line, e.position.col, e.code.to_string())),
+            ConfigFormat::Toml => toml::from_str(text).map_err(|e| {
+                let (line, column) = e
+                    .span()
+                    .map_or((0, 0), |span| line_column(text, span.start));
+                error(line, column, e.message().to_string())
+            }),
+            ConfigFormat::Json => serde_json::from_str(text).map_err(|e| {
+                let message = e.to_string();
+                // drop the " at line X column Y" suffix, it is in the error anyway
+                let message = message
+                    .rsplit_once(" at line ")
+                    .map_or(message.as_str(), |x| x.0)
+                    .to_string();
+                error(e.line(), e.column(), message)
+            }),
+            ConfigFormat::Yaml => serde_yaml::from_str(text).map_err(|e| {
+                let (line, column) = e.location().map_or((0, 0), |x| (x.line(), x.column()));
+                let message = e.to_string();
+                let message = message
+                    .rsplit_once(" at line ")
+                    .map_or(message.as_str(), |x| x.0)
+                    .to_string();
+                error(line, column, message)
+            }),
+        }
+    }
+
+    /// Serializes a value in this format
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String, ConfigError> {
+        let error = |message: String| ConfigError::Serialize {
+            format: *self,
+            message,
+        };
+        match self {
+            ConfigFormat::Ron => {
+                ron::ser::to_string_pretty(value, PrettyConfig::default().enumerate_arrays(true))
+                    .map_err(|e| error(e.to_string()))
+            }
+            ConfigFormat::Toml => toml::to_string_pretty(value).map_err(|e| error(e.to_string())),
+            ConfigFormat::Json => {
+                serde_json::to_string_pretty(value).map_err(|e| error(e.to_string()))
+            }
+            ConfigFormat::Yaml => serde_yaml::to_string(value).map_err(|e| error(e.to_string())),
+        }
+    }
+}
+
+/// 1-based line and column of a byte offset
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before.len(), |i| before.len() - i - 1)
+        + 1;
+    (line, column)
+}
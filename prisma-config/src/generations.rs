@@ -0,0 +1,16 @@
+//! Retention of deployment generations.
+use serde::{Deserialize, Serialize};
+
+/// How many generations are kept for rollbacks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationOptions {
+    /// Newest generations to keep, the active one is always kept, `0` keeps all of them
+    pub keep: usize,
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        Self { keep: 10 }
+    }
+}
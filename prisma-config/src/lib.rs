@@ -1 +1,9 @@
 pub mod config;
+pub mod edit;
+pub mod error;
+pub mod format;
+pub mod generations;
+pub mod network;
+pub mod properties;
+pub mod validate;
+pub use error::ConfigError;
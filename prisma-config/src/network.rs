@@ -0,0 +1,75 @@
+//! HTTP settings shared by every provider.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// HTTP client used to talk to providers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkOptions {
+    /// Replaces the whole `User-Agent` header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Contact appended to the default `User-Agent`, like an email, Modrinth asks for one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+    /// Seconds to establish a connection
+    pub connect_timeout: u64,
+    /// Seconds to wait for data on an open connection
+    pub read_timeout: u64,
+    /// Retries of a request failed with 5xx, 429 or a connection error
+    pub retries: u32,
+    /// Proxy for all requests, like `http://proxy.local:3128`, `HTTP(S)_PROXY` are used if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// PEM files with extra root certificates
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ca_certificates: Vec<String>,
+    /// Never touch the network, resolve from the cache and the store
+    pub offline: bool,
+    /// Seconds a cached provider answer is used without asking again, `0` always revalidates
+    pub cache_ttl: u64,
+    /// Requests in flight at once, downloads included, `0` is unlimited
+    pub concurrency: usize,
+    /// Requests in flight to the same host, `0` is unlimited
+    pub per_host: usize,
+    /// Base URLs and cache TTLs by provider name, like `papermc` or `modrinth`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub endpoints: BTreeMap<String, Endpoint>,
+}
+
+/// Base URLs and cache TTL of a provider
+///
+/// Mirrors are tried in order when the base URL is unreachable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Endpoint {
+    /// Replaces the built-in base URL, like `https://papermc.mirror.local/v2`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Fallback base URLs
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
+    /// Replaces [NetworkOptions::cache_ttl] for this provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+}
+
+impl Default for NetworkOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: None,
+            contact: None,
+            connect_timeout: 10,
+            read_timeout: 30,
+            retries: 3,
+            proxy: None,
+            ca_certificates: vec![],
+            offline: false,
+            cache_ttl: 600,
+            concurrency: 16,
+            per_host: 6,
+            endpoints: BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Keys of `server.properties` managed by the config.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Server properties for configuring Minecraft server behavior
+///
+/// Covers the vanilla key set, anything else goes to `extra`.
+/// Keys that are `None` aren't managed and stay as they are in `server.properties`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerProperties {
+    /// Server MOTD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub motd: Option<String>,
+    /// Max players
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_players: Option<u32>,
+    /// Online mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub online_mode: Option<bool>,
+    /// Difficulty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Gamemode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gamemode: Option<Gamemode>,
+    /// View distance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_distance: Option<u32>,
+    /// Allow nether
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_nether: Option<bool>,
+    /// Enable command blocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_command_block: Option<bool>,
+    /// Accept players transferred from other servers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepts_transfers: Option<bool>,
+    /// Allow flight in survival
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_flight: Option<bool>,
+    /// Send console command output to ops
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast_console_to_ops: Option<bool>,
+    /// Send rcon command output to ops
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast_rcon_to_ops: Option<bool>,
+    /// Expose JMX metrics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_jmx_monitoring: Option<bool>,
+    /// Enable GameSpy4 query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_query: Option<bool>,
+    /// Enable remote console
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_rcon: Option<bool>,
+    /// Show the server as online in the server list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_status: Option<bool>,
+    /// Require signed chat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enforce_secure_profile: Option<bool>,
+    /// Kick players not in the whitelist on reload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enforce_whitelist: Option<bool>,
+    /// Entity tracking range, in percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_broadcast_range_percentage: Option<u32>,
+    /// Force the default gamemode on join
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_gamemode: Option<bool>,
+    /// Permission level of functions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_permission_level: Option<u8>,
+    /// Generate structures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generate_structures: Option<bool>,
+    /// Generator settings as JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator_settings: Option<String>,
+    /// Hardcore mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hardcore: Option<bool>,
+    /// Hide the player list in the server status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_online_players: Option<bool>,
+    /// Datapacks disabled on world creation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_disabled_packs: Option<String>,
+    /// Datapacks enabled on world creation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_enabled_packs: Option<String>,
+    /// World directory name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_name: Option<String>,
+    /// World seed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_seed: Option<String>,
+    /// World preset, like `minecraft:normal`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_type: Option<String>,
+    /// Log player IP addresses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_ips: Option<bool>,
+    /// Limit of chained neighbor updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_chained_neighbor_updates: Option<i32>,
+    /// Watchdog timeout of a tick, in ms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tick_time: Option<i64>,
+    /// World border radius
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_world_size: Option<u32>,
+    /// Packet size to compress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_compression_threshold: Option<i32>,
+    /// Permission level of ops
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub op_permission_level: Option<u8>,
+    /// Kick idle players after minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_idle_timeout: Option<u32>,
+    /// Kick players connecting through a proxy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prevent_proxy_connections: Option<bool>,
+    /// Player versus player
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pvp: Option<bool>,
+    /// Query port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_port: Option<u16>,
+    /// Packets per second before kick
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    /// Remote console password
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rcon_password: Option<String>,
+    /// Remote console port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rcon_port: Option<u16>,
+    /// Region file compression algorithm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region_file_compression: Option<String>,
+    /// Kick players declining the resource pack
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_resource_pack: Option<bool>,
+    /// Resource pack url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pack: Option<String>,
+    /// Resource pack UUID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pack_id: Option<String>,
+    /// Resource pack prompt text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pack_prompt: Option<String>,
+    /// Resource pack SHA1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pack_sha1: Option<String>,
+    /// Address to bind
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_ip: Option<String>,
+    /// Simulation distance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simulation_distance: Option<u32>,
+    /// Spawn animals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spawn_animals: Option<bool>,
+    /// Spawn monsters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spawn_monsters: Option<bool>,
+    /// Spawn villagers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spawn_npcs: Option<bool>,
+    /// Spawn protection radius
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spawn_protection: Option<u32>,
+    /// Write chunks synchronously
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_chunk_writes: Option<bool>,
+    /// Text filtering config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_filtering_config: Option<String>,
+    /// Use native network transport on Linux
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_native_transport: Option<bool>,
+    /// Enable the whitelist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub white_list: Option<bool>,
+    /// Keys without a field, like modded or newer ones, by their `server.properties` name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+/// Server difficulty levels
+#[derive(Debug, Clone, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Difficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+/// Server game modes
+#[derive(Debug, Clone, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Gamemode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl ServerProperties {
+    /// Declared keys and values as they are written to `server.properties`
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        macro_rules! entry {
+            ($key:literal, $field:ident) => {
+                if let Some(value) = &self.$field {
+                    entries.push(($key.to_string(), value.to_string()));
+                }
+            };
+        }
+        entry!("motd", motd);
+        entry!("max-players", max_players);
+        entry!("online-mode", online_mode);
+        entry!("difficulty", difficulty);
+        entry!("gamemode", gamemode);
+        entry!("view-distance", view_distance);
+        entry!("allow-nether", allow_nether);
+        entry!("enable-command-block", enable_command_block);
+        entry!("accepts-transfers", accepts_transfers);
+        entry!("allow-flight", allow_flight);
+        entry!("broadcast-console-to-ops", broadcast_console_to_ops);
+        entry!("broadcast-rcon-to-ops", broadcast_rcon_to_ops);
+        entry!("enable-jmx-monitoring", enable_jmx_monitoring);
+        entry!("enable-query", enable_query);
+        entry!("enable-rcon", enable_rcon);
+        entry!("enable-status", enable_status);
+        entry!("enforce-secure-profile", enforce_secure_profile);
+        entry!("enforce-whitelist", enforce_whitelist);
+        entry!(
+            "entity-broadcast-range-percentage",
+            entity_broadcast_range_percentage
+        );
+        entry!("force-gamemode", force_gamemode);
+        entry!("function-permission-level", function_permission_level);
+        entry!("generate-structures", generate_structures);
+        entry!("generator-settings", generator_settings);
+        entry!("hardcore", hardcore);
+        entry!("hide-online-players", hide_online_players);
+        entry!("initial-disabled-packs", initial_disabled_packs);
+        entry!("initial-enabled-packs", initial_enabled_packs);
+        entry!("level-name", level_name);
+        entry!("level-seed", level_seed);
+        entry!("level-type", level_type);
+        entry!("log-ips", log_ips);
+        entry!("max-chained-neighbor-updates", max_chained_neighbor_updates);
+        entry!("max-tick-time", max_tick_time);
+        entry!("max-world-size", max_world_size);
+        entry!(
+            "network-compression-threshold",
+            network_compression_threshold
+        );
+        entry!("op-permission-level", op_permission_level);
+        entry!("player-idle-timeout", player_idle_timeout);
+        entry!("prevent-proxy-connections", prevent_proxy_connections);
+        entry!("pvp", pvp);
+        entry!("query.port", query_port);
+        entry!("rate-limit", rate_limit);
+        entry!("rcon.password", rcon_password);
+        entry!("rcon.port", rcon_port);
+        entry!("region-file-compression", region_file_compression);
+        entry!("require-resource-pack", require_resource_pack);
+        entry!("resource-pack", resource_pack);
+        entry!("resource-pack-id", resource_pack_id);
+        entry!("resource-pack-prompt", resource_pack_prompt);
+        entry!("resource-pack-sha1", resource_pack_sha1);
+        entry!("server-ip", server_ip);
+        entry!("simulation-distance", simulation_distance);
+        entry!("spawn-animals", spawn_animals);
+        entry!("spawn-monsters", spawn_monsters);
+        entry!("spawn-npcs", spawn_npcs);
+        entry!("spawn-protection", spawn_protection);
+        entry!("sync-chunk-writes", sync_chunk_writes);
+        entry!("text-filtering-config", text_filtering_config);
+        entry!("use-native-transport", use_native_transport);
+        entry!("white-list", white_list);
+        entries.extend(self.extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+        entries
+    }
+}
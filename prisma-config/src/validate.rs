@@ -0,0 +1,241 @@
+//! Semantic checks of a parsed config, run before anything is downloaded.
+use std::net::TcpListener;
+
+use prisma_core::{extension::ExtensionType, platform::Platform};
+
+use crate::config::Config;
+
+/// How bad a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display, strum::EnumIs)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// Config works, but probably not as intended
+    Warning,
+    /// Config can't be deployed
+    Error,
+}
+
+/// Single problem found in the config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Path to the value, like `extensions[3].platform`
+    pub path: String,
+    pub message: String,
+    /// How to fix it
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(path, message)
+        }
+    }
+
+    fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.path, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n  help: {}", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Checks the config for mistakes that would break the deployment
+    ///
+    /// Only the config is looked at, see [Config::check_port] for the host. Diagnostics are
+    /// grouped by section: core first, then extensions and options.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        self.validate_core(&mut diagnostics);
+        self.validate_extensions(&mut diagnostics);
+        self.validate_options(&mut diagnostics);
+        diagnostics
+    }
+
+    fn validate_core(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let numeric_builds = matches!(
+            self.core.platform,
+            Platform::Paper
+                | Platform::Folia
+                | Platform::Waterfall
+                | Platform::Velocity
+                | Platform::Purpur
+        );
+        if let Some(build) = &self.core.version.version_build {
+            if numeric_builds && build.parse::<u32>().is_err() {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "core.version.version_build",
+                        format!("{} builds are numbers, got {:?}", self.core.platform, build),
+                    )
+                    .suggest("use a build number like Some(\"17\"), or None for the latest"),
+                );
+            }
+        }
+        let version = &self.core.version;
+        if !self.core.platform.supports_mods()
+            && (version.loader_version.is_some() || version.installer_version.is_some())
+        {
+            diagnostics.push(
+                Diagnostic::warning(
+                    "core.version.loader_version",
+                    format!(
+                        "{} has no mod loader, the pin is ignored",
+                        self.core.platform
+                    ),
+                )
+                .suggest("remove loader_version and installer_version"),
+            );
+        }
+    }
+
+    fn validate_extensions(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let core = &self.core.platform;
+
+        for (index, extension) in self.extensions.iter().enumerate() {
+            let path = format!("extensions[{}]", index);
+
+            if let Some(first) = self.extensions[..index]
+                .iter()
+                .position(|x| x.name == extension.name)
+            {
+                diagnostics.push(
+                    Diagnostic::error(
+                        format!("{}.name", path),
+                        format!(
+                            "{} is already declared at extensions[{}]",
+                            extension.name, first
+                        ),
+                    )
+                    .suggest("remove one of the entries"),
+                );
+            }
+
+            match &extension.provider {
+                ExtensionType::Mod(_) if !core.supports_mods() => diagnostics.push(
+                    Diagnostic::error(
+                        format!("{}.provider", path),
+                        format!("mod {} can't be loaded by {}", extension.name, core),
+                    )
+                    .suggest(if core.supports_plugins() {
+                        "declare it as Plugin(..) or use a mod platform like Fabric"
+                    } else {
+                        "use a mod platform like Fabric, Quilt, Forge or NeoForge"
+                    }),
+                ),
+                ExtensionType::Plugin(_) if !core.supports_plugins() => diagnostics.push(
+                    Diagnostic::error(
+                        format!("{}.provider", path),
+                        format!("plugin {} can't be loaded by {}", extension.name, core),
+                    )
+                    .suggest(if core.supports_mods() {
+                        "declare it as Mod(..) or use a plugin platform like Paper"
+                    } else {
+                        "use a plugin platform like Paper or Purpur"
+                    }),
+                ),
+                _ => {}
+            }
+
+            if let Some(platform) = &extension.platform {
+                if platform != core {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            format!("{}.platform", path),
+                            format!(
+                                "{} is resolved for {}, but the core is {}",
+                                extension.name, platform, core
+                            ),
+                        )
+                        .suggest("set platform to None to inherit it from the core"),
+                    );
+                }
+            }
+
+            if let (Some(pinned), Some(core_version)) = (
+                &extension.version.game_version,
+                &self.core.version.game_version,
+            ) {
+                if pinned != core_version {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            format!("{}.version.game_version", path),
+                            format!(
+                                "{} is pinned to {}, but the core is {}",
+                                extension.name, pinned, core_version
+                            ),
+                        )
+                        .suggest("set game_version to None to inherit it from the core"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_options(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let options = &self.options;
+
+        if options.min_memory == 0 {
+            diagnostics.push(
+                Diagnostic::error("options.min_memory", "memory must be greater than 0")
+                    .suggest("set min_memory in MB, like 1024"),
+            );
+        }
+        if options.min_memory > options.max_memory {
+            diagnostics.push(
+                Diagnostic::error(
+                    "options.min_memory",
+                    format!(
+                        "min_memory ({} MB) is greater than max_memory ({} MB)",
+                        options.min_memory, options.max_memory
+                    ),
+                )
+                .suggest(format!(
+                    "lower min_memory to {} or raise max_memory",
+                    options.max_memory
+                )),
+            );
+        }
+
+        if options.port == 0 {
+            diagnostics.push(
+                Diagnostic::error("options.port", "port 0 isn't a valid server port")
+                    .suggest("use the default port 25565"),
+            );
+        }
+    }
+
+    /// Checks that the server port is free by binding it for a moment
+    ///
+    /// Fails while the server itself is running, so it isn't part of [Config::validate].
+    pub fn check_port(&self) -> Option<Diagnostic> {
+        let port = self.options.port;
+        if port == 0 || TcpListener::bind(("0.0.0.0", port)).is_ok() {
+            return None;
+        }
+        Some(
+            Diagnostic::warning("options.port", format!("port {} is already in use", port))
+                .suggest("stop the process that uses it or pick another port"),
+        )
+    }
+}
@@ -1,7 +1,12 @@
 #[cfg(test)]
 mod config {
-    use prisma_config::config::{
-        Config, CoreConfig, CoreOptions, Difficulty, ExtensionConfig, Gamemode, ServerProperties,
+    use prisma_config::{
+        config::{
+            Config, CoreConfig, CoreOptions, Difficulty, ExtensionConfig, Gamemode,
+            ServerProperties,
+        },
+        format::ConfigFormat,
+        ConfigError,
     };
     use prisma_core::{extension::ExtensionType, platform::Platform, version::Version};
     use ron::ser::PrettyConfig;
@@ -36,6 +41,7 @@ mod config {
                     view_distance: Some(10),
                     allow_nether: Some(true),
                     enable_command_block: Some(false),
+                    ..Default::default()
                 },
             })
             .add_extension(ExtensionConfig {
@@ -66,11 +72,49 @@ mod config {
 
         let toml = toml::to_string_pretty(&config).unwrap();
         let json = serde_json::to_string_pretty(&config).unwrap();
+        let yaml = serde_yaml::to_string(&config).unwrap();
         let ron =
             ron::ser::to_string_pretty(&config, PrettyConfig::default().enumerate_arrays(true))
                 .unwrap();
         std::fs::write("test_config.toml", toml).unwrap();
         std::fs::write("test_config.json", json).unwrap();
         std::fs::write("test_config.ron", ron).unwrap();
+        std::fs::write("test_config.yaml", yaml).unwrap();
+    }
+
+    #[tokio::test]
+    async fn parse_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::new().with_core(CoreConfig {
+            platform: Platform::Fabric,
+            ..Default::default()
+        });
+
+        for name in ["prisma.ron", "prisma.toml", "prisma.json", "prisma.yaml"] {
+            let path = dir.path().join(name).to_string_lossy().to_string();
+            config.save_config(Some(&path)).await.unwrap();
+            let parsed = Config::parse_config(Some(&path)).await.unwrap();
+            assert_eq!(parsed.core.platform, Platform::Fabric, "{}", name);
+        }
+
+        assert!(matches!(
+            Config::parse_config(Some("prisma.ini")).await,
+            Err(ConfigError::UnknownFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let text = "[core]\nplatform = \"Paper\"\nversion = 1\n";
+        match ConfigFormat::Toml.parse::<Config>(text, "prisma.toml") {
+            Err(ConfigError::Parse { line, column, .. }) => assert_eq!((line, column), (3, 11)),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let text = "{\n  \"core\": 1\n}";
+        match ConfigFormat::Json.parse::<Config>(text, "prisma.json") {
+            Err(ConfigError::Parse { line, .. }) => assert_eq!(line, 2),
+            other => panic!("unexpected {:?}", other),
+        }
     }
 }
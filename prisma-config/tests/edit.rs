@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod edit {
+    use prisma_config::{config::ExtensionConfig, edit::ConfigDocument};
+    use prisma_core::extension::{ExtensionProvider, ExtensionType};
+
+    const SOURCE: &str = r#"// my server
+(
+    core: (
+        platform: Paper,
+        version: (game_version: Some("1.20.1"), version_build: None, channel: Release),
+        options: (freeze: false, force_update: false),
+    ),
+    extensions: [
+        // permissions
+        (name: "luckperms", platform: None, provider: Plugin(Modrinth)),
+        (name: "vault", platform: None, provider: Plugin(Modrinth)), // economy
+    ],
+    options: (
+        port: 25565,
+        min_memory: 1024,
+        max_memory: 2048,
+        java_args: [],
+        properties: (),
+    ),
+)
+"#;
+
+    fn extension(name: &str) -> ExtensionConfig {
+        ExtensionConfig {
+            name: name.to_string(),
+            platform: None,
+            provider: ExtensionType::Plugin(ExtensionProvider::Modrinth),
+            version: Default::default(),
+            options: Default::default(),
+        }
+    }
+
+    #[test]
+    fn add_keeps_comments() {
+        let mut doc = ConfigDocument::new(SOURCE);
+        doc.add_extension(&extension("worldedit")).unwrap();
+
+        let names = doc
+            .extensions()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["luckperms", "vault", "worldedit"]);
+        assert!(doc.as_str().starts_with("// my server\n"));
+        assert!(doc.as_str().contains("// permissions"));
+        assert!(doc.as_str().contains("// economy"));
+        assert!(doc.add_extension(&extension("vault")).is_err());
+    }
+
+    #[test]
+    fn add_to_empty_list() {
+        let mut doc = ConfigDocument::new("(core: (), extensions: [], options: ())");
+        doc.add_extension(&extension("worldedit")).unwrap();
+        assert_eq!(doc.extensions().unwrap()[0].name, "worldedit");
+    }
+
+    #[test]
+    fn remove_with_attached_comments() {
+        let mut doc = ConfigDocument::new(SOURCE);
+        let plugin = ExtensionType::Plugin(ExtensionProvider::Modrinth);
+        let removed = doc.remove_extension("luckperms", &plugin).unwrap();
+        assert_eq!(removed.map(|e| e.name).as_deref(), Some("luckperms"));
+        assert!(!doc.as_str().contains("// permissions"));
+        assert!(doc.as_str().contains("// economy"));
+
+        // a mod of the same name isn't declared
+        let r#mod = ExtensionType::Mod(ExtensionProvider::Modrinth);
+        assert!(doc.remove_extension("vault", &r#mod).unwrap().is_none());
+        assert!(doc.as_str().contains("// economy"));
+
+        doc.remove_extension("vault", &plugin).unwrap();
+        assert!(!doc.as_str().contains("// economy"));
+        assert!(doc.extensions().unwrap().is_empty());
+        assert!(doc.remove_extension("vault", &plugin).unwrap().is_none());
+        assert!(doc.as_str().contains("max_memory: 2048"));
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod validate {
+    use prisma_config::{
+        config::{Config, CoreConfig, CoreOptions, ExtensionConfig},
+        validate::Severity,
+    };
+    use prisma_core::{
+        extension::{ExtensionProvider, ExtensionType},
+        platform::Platform,
+        version::Version,
+    };
+
+    fn extension(name: &str, provider: ExtensionType) -> ExtensionConfig {
+        ExtensionConfig {
+            name: name.to_string(),
+            platform: None,
+            provider,
+            version: Default::default(),
+            options: Default::default(),
+        }
+    }
+
+    fn paths(config: &Config, severity: Severity) -> Vec<String> {
+        config
+            .validate()
+            .into_iter()
+            .filter(|x| x.severity == severity)
+            .map(|x| x.path)
+            .collect()
+    }
+
+    #[test]
+    fn default_is_valid() {
+        assert!(paths(&Config::new(), Severity::Error).is_empty());
+
+        let config = Config::new().with_options(CoreOptions {
+            port: 0,
+            ..Default::default()
+        });
+        assert_eq!(paths(&config, Severity::Error), ["options.port"]);
+    }
+
+    #[test]
+    fn catches_mistakes() {
+        let config = Config::new()
+            .with_core(CoreConfig {
+                platform: Platform::Paper,
+                version: Version {
+                    game_version: Some("1.20.1".to_string()),
+                    version_build: Some("latest".to_string()),
+                    ..Default::default()
+                },
+                options: Default::default(),
+            })
+            .with_options(CoreOptions {
+                min_memory: 4096,
+                max_memory: 2048,
+                ..Default::default()
+            })
+            .add_extension(extension(
+                "worldedit",
+                ExtensionType::Plugin(ExtensionProvider::Modrinth),
+            ))
+            .add_extension(extension(
+                "sodium",
+                ExtensionType::Mod(ExtensionProvider::Modrinth),
+            ))
+            .add_extension(ExtensionConfig {
+                version: Version {
+                    game_version: Some("1.19.4".to_string()),
+                    ..Default::default()
+                },
+                ..extension(
+                    "worldedit",
+                    ExtensionType::Plugin(ExtensionProvider::Modrinth),
+                )
+            });
+
+        assert_eq!(
+            paths(&config, Severity::Error),
+            [
+                "core.version.version_build",
+                "extensions[1].provider",
+                "extensions[2].name",
+                "options.min_memory",
+            ]
+        );
+        assert_eq!(
+            paths(&config, Severity::Warning),
+            ["extensions[2].version.game_version"]
+        );
+        assert!(config.validate().iter().all(|x| x.suggestion.is_some()));
+    }
+
+    #[test]
+    fn port_in_use_is_only_checked_on_request() {
+        let listener = std::net::TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let config = Config::new().with_options(CoreOptions {
+            port: listener.local_addr().unwrap().port(),
+            ..Default::default()
+        });
+        assert!(config.validate().is_empty());
+        assert_eq!(
+            config.check_port().map(|x| (x.severity, x.path)),
+            Some((Severity::Warning, "options.port".to_string()))
+        );
+    }
+}
@@ -1,2 +1,3 @@
+pub mod time;
 pub mod types;
 pub use types::*;
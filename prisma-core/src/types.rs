@@ -14,13 +14,41 @@ pub const STORE_TEMP_DIR: &str = "./.prisma/.temp";
 pub const STORE_CORES_DIR: &str = "./.prisma/cors";
 pub const STORE_PLUGINS_DIR: &str = "./.prisma/extensions/plugins";
 pub const STORE_MODS_DIR: &str = "./.prisma/extensions/mods";
+/// Loader libraries, in maven layout
+pub const STORE_LIBRARIES_DIR: &str = "./.prisma/libraries";
+/// Output of installers and build tools
+pub const STORE_LOGS_DIR: &str = "./.prisma/logs";
+/// Answers of provider APIs
+pub const STORE_CACHE_DIR: &str = "./.prisma/cache";
 
 pub const STORE_PATH: &str = "./.prisma/store.ron";
+/// Numbered snapshots of the deployment
+pub const STORE_GENERATIONS_DIR: &str = "./.prisma/generations";
+/// Link to the active generation, deployed files are linked through it
+pub const CURRENT_GENERATION_PATH: &str = "./.prisma/current";
+pub const PLAN_PATH: &str = "./.prisma/plan.ron";
+pub const SERVER_STATE_PATH: &str = "./.prisma/server.ron";
+/// Values of `server.properties` written by the last sync
+pub const PROPERTIES_STATE_PATH: &str = "./.prisma/properties.ron";
 
 // Config
 pub const CONFIG_PATH: &str = "./prisma.ron";
+/// Where to look for a config, in order
+pub const CONFIG_PATHS: [&str; 5] = [
+    CONFIG_PATH,
+    "./prisma.toml",
+    "./prisma.json",
+    "./prisma.yaml",
+    "./prisma.yml",
+];
+/// Exact resolved set of the config, committed next to it
+pub const LOCK_PATH: &str = "./prisma.lock";
 
 // minecraft
 pub const PLUGINS_DIR: &str = "./plugins";
 pub const MODS_DIR: &str = "./mods";
+pub const LIBRARIES_DIR: &str = "./libraries";
 pub const CORE_DIR: &str = "./";
+pub const SERVER_PROPERTIES_PATH: &str = "./server.properties";
+/// JVM arguments of Forge `run.sh`
+pub const USER_JVM_ARGS_PATH: &str = "./user_jvm_args.txt";
@@ -17,6 +17,16 @@ pub enum ExtensionProvider {
     // Plugins & mods
     #[default]
     Modrinth,
+    /// Provider registered outside of prisma, by its registry name
+    #[strum(default)]
+    Custom(String),
+}
+
+impl ExtensionProvider {
+    /// Registry name, like `modrinth`
+    pub fn key(&self) -> String {
+        self.to_string().to_lowercase()
+    }
 }
 #[derive(
     Debug,
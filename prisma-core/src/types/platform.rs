@@ -13,6 +13,7 @@
     strum::EnumIter,
     strum::EnumIs,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum Platform {
     // Mojang
     #[default]
@@ -38,4 +39,44 @@ pub enum Platform {
     Quilt,
     Forge,
     NeoForge,
+
+    /// Platform of a provider registered outside of prisma, by its registry name
+    #[strum(default)]
+    Custom(String),
+}
+
+impl Platform {
+    /// Platform can load plugins (Bukkit API or proxy plugins)
+    ///
+    /// Custom platforms are assumed to load both plugins and mods.
+    pub fn supports_plugins(&self) -> bool {
+        matches!(
+            self,
+            Platform::Custom(_)
+                | Platform::Spigot
+                | Platform::Bukkit
+                | Platform::Paper
+                | Platform::Folia
+                | Platform::Waterfall
+                | Platform::Velocity
+                | Platform::Purpur
+        )
+    }
+
+    /// Platform can load mods
+    pub fn supports_mods(&self) -> bool {
+        matches!(
+            self,
+            Platform::Fabric
+                | Platform::Quilt
+                | Platform::Forge
+                | Platform::NeoForge
+                | Platform::Custom(_)
+        )
+    }
+
+    /// Registry name, like `paper`
+    pub fn key(&self) -> String {
+        self.to_string().to_lowercase()
+    }
 }
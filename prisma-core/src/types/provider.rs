@@ -19,6 +19,23 @@ pub enum Provider {
 
 pub type Name = String;
 
+impl Provider {
+    /// Human readable name, like `core Paper` or `plugin worldedit`
+    pub fn describe(&self) -> String {
+        match self {
+            Provider::Core(platform) => format!("core {}", platform),
+            Provider::Extension((name, _, ext)) => {
+                format!("{} {}", ext.as_ref().to_lowercase(), name)
+            }
+        }
+    }
+
+    /// Is the mod or plugin `name`, like `kind`, whatever its provider
+    pub fn is_extension_named(&self, name: &str, kind: &ExtensionType) -> bool {
+        matches!(self, Provider::Extension((x, _, ext)) if x == name && ext.as_ref() == kind.as_ref())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
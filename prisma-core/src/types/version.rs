@@ -9,9 +9,40 @@ pub type GameVersionBuild = String;
 /// - if channel is none -> Release
 ///
 /// - if version & version build isn't empty -> version build
+/// - if loader or installer version is none -> Latest, only used by mod loaders
 #[derive(Debug, Default, Clone, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize)]
 pub struct Version {
     pub game_version: Option<String>,
     pub version_build: Option<String>,
     pub channel: Channel,
+    /// Mod loader version, like Fabric loader `0.16.5`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loader_version: Option<String>,
+    /// Loader installer version, like Fabric installer `1.0.1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installer_version: Option<String>,
+}
+
+impl Version {
+    /// Human readable version, like `1.20.1 (17)` or `1.21 loader 0.16.5`
+    pub fn describe(&self) -> String {
+        let game_version = self.game_version.as_deref().unwrap_or("latest");
+        let mut description = match &self.version_build {
+            Some(build) => format!("{} ({})", game_version, build),
+            None => game_version.to_string(),
+        };
+        if let Some(loader) = &self.loader_version {
+            description.push_str(&format!(" loader {}", loader));
+        }
+        description
+    }
+
+    /// Every version pinned by `pin` is this one, unpinned versions match any
+    pub fn satisfies(&self, pin: &Version) -> bool {
+        let pinned =
+            |pin: &Option<String>, version: &Option<String>| pin.is_none() || pin == version;
+        pinned(&pin.game_version, &self.game_version)
+            && pinned(&pin.version_build, &self.version_build)
+            && pinned(&pin.loader_version, &self.loader_version)
+    }
 }
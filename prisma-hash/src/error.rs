@@ -0,0 +1,14 @@
+use crate::HashType;
+
+/// Errors of hash validation
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum HashError {
+    /// Data doesn't match the expected hash
+    #[error("hash mismatch: expected {expected} but got {actual}")]
+    Mismatch {
+        /// Hash the data should have
+        expected: HashType,
+        /// Hash of the data, same algorithm as `expected`
+        actual: String,
+    },
+}
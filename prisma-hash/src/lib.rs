@@ -1,5 +1,9 @@
 use sha1::{Digest, Sha1};
 use sha2::{Sha256, Sha512};
+
+pub mod error;
+pub use error::HashError;
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum HashType {
     SHA1(String),
@@ -51,44 +55,91 @@ impl HashType {
     }
 
     /// Return error if hash is't compare
-    pub fn compare(
-        &self,
-        data: impl AsRef<[u8]>,
-    ) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-        match self {
-            HashType::SHA1(expected) => {
-                let hash = Self::compute_sha1(data);
-                if expected == &hash {
-                    Ok(())
-                } else {
-                    Err(format!("Hash mismatch: expected {} but got {}", expected, hash).into())
-                }
-            }
-            HashType::SHA512(expected) => {
-                let hash = Self::compute_sha512(data);
-                if expected == &hash {
-                    Ok(())
-                } else {
-                    Err(format!("Hash mismatch: expected {} but got {}", expected, hash).into())
-                }
-            }
-            HashType::MD5(expected) => {
-                let hash = Self::compute_md5(data);
-                if expected == &hash {
-                    Ok(())
-                } else {
-                    Err(format!("Hash mismatch: expected {} but got {}", expected, hash).into())
-                }
-            }
-            HashType::SHA256(expected) => {
-                let hash = Self::compute_sha256(data);
-                if expected == &hash {
-                    Ok(())
-                } else {
-                    Err(format!("Hash mismatch: expected {} but got {}", expected, hash).into())
-                }
+    pub fn compare(&self, data: impl AsRef<[u8]>) -> Result<(), HashError> {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    /// Hasher of data read in chunks, checked against this hash
+    pub fn hasher(&self) -> Hasher {
+        let state = match self {
+            HashType::SHA1(_) => State::SHA1(Sha1::new()),
+            HashType::SHA512(_) => State::SHA512(Sha512::new()),
+            HashType::SHA256(_) => State::SHA256(Sha256::new()),
+            HashType::MD5(_) => State::MD5(md5::Context::new()),
+            HashType::None => State::None,
+        };
+        Hasher {
+            expected: self.clone(),
+            state,
+        }
+    }
+}
+
+/// Incremental hash of streamed data, see [HashType::hasher]
+#[derive(Clone)]
+pub struct Hasher {
+    expected: HashType,
+    state: State,
+}
+
+#[derive(Clone)]
+enum State {
+    SHA1(Sha1),
+    SHA512(Sha512),
+    SHA256(Sha256),
+    MD5(md5::Context),
+    None,
+}
+
+impl Hasher {
+    /// Sha256 of data that isn't checked against a hash, read it with [Hasher::digest]
+    pub fn sha256() -> Self {
+        Self {
+            expected: HashType::None,
+            state: State::SHA256(Sha256::new()),
+        }
+    }
+
+    /// Hashes the next chunk
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        match &mut self.state {
+            State::SHA1(hasher) => hasher.update(data),
+            State::SHA512(hasher) => hasher.update(data),
+            State::SHA256(hasher) => hasher.update(data),
+            State::MD5(context) => context.consume(data),
+            State::None => {}
+        }
+    }
+
+    /// Hex digest of the hashed data, `None` for [HashType::None]
+    pub fn digest(self) -> Option<String> {
+        match self.state {
+            State::SHA1(hasher) => Some(format!("{:x}", hasher.finalize())),
+            State::SHA512(hasher) => Some(format!("{:x}", hasher.finalize())),
+            State::SHA256(hasher) => Some(format!("{:x}", hasher.finalize())),
+            State::MD5(context) => Some(format!("{:x}", context.compute())),
+            State::None => None,
+        }
+    }
+
+    /// Return error if the hashed data doesn't match the expected hash
+    pub fn finish(self) -> Result<(), HashError> {
+        let expected = self.expected.clone();
+        let Some(actual) = self.digest() else {
+            return Ok(());
+        };
+        let matches = match &expected {
+            HashType::SHA1(x) | HashType::SHA512(x) | HashType::SHA256(x) | HashType::MD5(x) => {
+                x == &actual
             }
-            HashType::None => Ok(()),
+            HashType::None => true,
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(HashError::Mismatch { expected, actual })
         }
     }
 }
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod hash {
+    use prisma_hash::{HashError, HashType, Hasher};
+
+    #[test]
+    fn compare() {
+        let data = b"prisma";
+        let sha1 = HashType::new_sha1(HashType::compute_sha1(data));
+        assert!(sha1.compare(data).is_ok());
+        assert!(HashType::None.compare(data).is_ok());
+
+        let expected = HashType::new_sha256("0".repeat(64));
+        assert_eq!(
+            expected.compare(data),
+            Err(HashError::Mismatch {
+                expected: expected.clone(),
+                actual: HashType::compute_sha256(data),
+            })
+        );
+    }
+
+    #[test]
+    fn hasher() {
+        let data = b"prisma store";
+        for expected in [
+            HashType::new_md5(HashType::compute_md5(data)),
+            HashType::new_sha1(HashType::compute_sha1(data)),
+            HashType::new_sha256(HashType::compute_sha256(data)),
+            HashType::new_sha512(HashType::compute_sha512(data)),
+        ] {
+            let mut hasher = expected.hasher();
+            for chunk in data.chunks(5) {
+                hasher.update(chunk);
+            }
+            assert!(hasher.finish().is_ok());
+        }
+
+        let mut hasher = Hasher::sha256();
+        hasher.update(data);
+        assert_eq!(hasher.digest(), Some(HashType::compute_sha256(data)));
+
+        let mut hasher = HashType::new_sha1(HashType::compute_sha1(data)).hasher();
+        hasher.update(b"prisma");
+        assert!(hasher.finish().is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Provider API answers kept on disk.
+//!
+//! Entries are keyed by URL and revalidated with `ETag`/`Last-Modified` once their
+//! provider TTL is over.
+use std::path::{Path, PathBuf};
+
+use prisma_core::time::now;
+use prisma_hash::HashType;
+use serde::{Deserialize, Serialize};
+
+use crate::ProviderError;
+
+/// Directory of cached answers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cache {
+    dir: PathBuf,
+    /// Revalidate every entry, regardless of its age
+    refresh: bool,
+}
+
+/// Cached answer of a GET request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Seconds since the unix epoch of the last fetch or revalidation
+    pub fetched_at: u64,
+    pub body: String,
+}
+
+impl Cache {
+    /// Cache in `dir`, created on the first write
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            refresh: false,
+        }
+    }
+
+    /// Revalidates every entry instead of trusting the fresh ones
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn refresh(&self) -> bool {
+        self.refresh
+    }
+
+    /// File of the entry for `url`
+    pub fn path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.ron", HashType::compute_sha256(url.as_bytes())))
+    }
+
+    /// Entry for `url`, a missing or unreadable entry is a miss
+    pub async fn get(&self, url: &str) -> Option<CacheEntry> {
+        let bytes = tokio::fs::read(self.path(url)).await.ok()?;
+        ron::de::from_bytes::<CacheEntry>(&bytes)
+            .ok()
+            .filter(|x| x.url == url)
+    }
+
+    /// Writes an entry, replacing the previous one in a single rename
+    pub async fn put(&self, entry: &CacheEntry) -> Result<(), ProviderError> {
+        let path = self.path(&entry.url);
+        let io_error = |source| ProviderError::Cache {
+            path: path.clone(),
+            source,
+        };
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(io_error)?;
+        let text = ron::ser::to_string(entry).map_err(|e| io_error(std::io::Error::other(e)))?;
+        let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+        tokio::fs::write(&temp, text).await.map_err(io_error)?;
+        tokio::fs::rename(&temp, &path).await.map_err(io_error)
+    }
+}
+
+impl CacheEntry {
+    /// Entry was fetched or revalidated less than `ttl` seconds ago
+    pub fn is_fresh(&self, ttl: u64) -> bool {
+        now().saturating_sub(self.fetched_at) < ttl
+    }
+}
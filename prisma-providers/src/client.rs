@@ -0,0 +1,339 @@
+//! HTTP client shared by every provider.
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use prisma_config::network::{Endpoint, NetworkOptions};
+use prisma_core::time::now;
+use reqwest::{
+    header::{HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    Request, RequestBuilder, Response, StatusCode,
+};
+
+use crate::{
+    cache::{Cache, CacheEntry},
+    limit::Limiter,
+    ProviderError,
+};
+
+static GLOBAL: RwLock<Option<Client>> = RwLock::new(None);
+
+/// First retry delay, doubled on every attempt
+const BACKOFF: Duration = Duration::from_millis(500);
+/// Longest wait between retries, `Retry-After` included
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Pooled HTTP client with the configured `User-Agent`, timeouts, proxy and certificates
+///
+/// Cloning is cheap, clones share the connection pool.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: reqwest::Client,
+    retries: u32,
+    endpoints: Arc<BTreeMap<String, Endpoint>>,
+    cache_ttl: u64,
+    cache: Option<Cache>,
+    offline: bool,
+    limiter: Limiter,
+}
+
+impl Client {
+    /// Builds a client from the config
+    pub fn new(options: &NetworkOptions) -> Result<Self, ProviderError> {
+        let user_agent = match (&options.user_agent, &options.contact) {
+            (Some(user_agent), _) => user_agent.clone(),
+            (None, Some(contact)) => format!("{} ({})", default_user_agent(), contact),
+            (None, None) => default_user_agent(),
+        };
+        let mut builder = reqwest::Client::builder()
+            .user_agent(user_agent)
+            .connect_timeout(Duration::from_secs(options.connect_timeout))
+            .read_timeout(Duration::from_secs(options.read_timeout));
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy).map_err(ProviderError::Client)?);
+        }
+        for path in &options.ca_certificates {
+            let pem = std::fs::read(path).map_err(|source| ProviderError::Certificate {
+                path: path.into(),
+                source,
+            })?;
+            for certificate in
+                reqwest::Certificate::from_pem_bundle(&pem).map_err(ProviderError::Client)?
+            {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        Ok(Self {
+            inner: builder.build().map_err(ProviderError::Client)?,
+            retries: options.retries,
+            endpoints: Arc::new(options.endpoints.clone()),
+            cache_ttl: options.cache_ttl,
+            cache: None,
+            offline: options.offline,
+            limiter: Limiter::new(options.concurrency, options.per_host),
+        })
+    }
+
+    /// Keeps answers of [Client::fetch] in the cache, not cached if `None`
+    pub fn with_cache(mut self, cache: Option<Cache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+    /// Answers only from the cache and fails every other request
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Limits of concurrent requests, held by [Client::fetch] and by downloads
+    pub fn limiter(&self) -> &Limiter {
+        &self.limiter
+    }
+
+    /// Client used by the built-in providers, built from the default options if not set
+    ///
+    /// Fails if the default client can't be built, like when no TLS backend is usable.
+    pub fn global() -> Result<Client, ProviderError> {
+        if let Some(client) = GLOBAL.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            return Ok(client.clone());
+        }
+        let mut global = GLOBAL.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(client) = global.as_ref() {
+            return Ok(client.clone());
+        }
+        let client = Client::new(&NetworkOptions::default())?;
+        *global = Some(client.clone());
+        Ok(client)
+    }
+
+    /// Replaces the global client
+    pub fn set_global(client: Client) {
+        *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(client);
+    }
+
+    /// Base URLs of the provider in the order to try, `default` unless overridden
+    pub fn endpoints(&self, provider: &str, default: &str) -> Vec<String> {
+        let endpoint = self.endpoints.get(provider);
+        std::iter::once(endpoint.and_then(|x| x.url.as_deref()).unwrap_or(default))
+            .chain(
+                endpoint
+                    .into_iter()
+                    .flat_map(|x| x.mirrors.iter().map(String::as_str)),
+            )
+            .map(|x| x.trim_end_matches('/').to_string())
+            .collect()
+    }
+
+    /// Seconds a cached answer of the provider is used without revalidation
+    pub fn ttl(&self, provider: &str) -> u64 {
+        self.endpoints
+            .get(provider)
+            .and_then(|x| x.ttl)
+            .unwrap_or(self.cache_ttl)
+    }
+
+    /// Starts a GET request
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.inner.get(url)
+    }
+
+    /// Sends a request, retrying 5xx, 429 and connection errors with a jittered backoff
+    ///
+    /// Error statuses are returned as [ProviderError::Status] and [ProviderError::RateLimited].
+    pub async fn send(
+        &self,
+        provider: &str,
+        request: RequestBuilder,
+    ) -> Result<Response, ProviderError> {
+        self.execute(provider, build(provider, request)?).await
+    }
+
+    /// Sends a GET request through the cache and returns the body
+    ///
+    /// Fresh entries are returned without a request, stale ones are revalidated.
+    /// Offline any entry is returned, a missing one is [ProviderError::NotCached].
+    pub async fn fetch(
+        &self,
+        provider: &str,
+        request: RequestBuilder,
+    ) -> Result<String, ProviderError> {
+        let mut request = build(provider, request)?;
+        let url = request.url().to_string();
+        let Some(cache) = &self.cache else {
+            if self.offline {
+                return Err(ProviderError::NotCached { what: url });
+            }
+            let _permit = self.limiter.acquire(&url).await;
+            return text(provider, self.execute(provider, request).await?).await;
+        };
+
+        let cached = cache.get(&url).await;
+        if self.offline {
+            return cached
+                .map(|x| x.body)
+                .ok_or(ProviderError::NotCached { what: url });
+        }
+        if let Some(entry) = &cached {
+            if !cache.refresh() && entry.is_fresh(self.ttl(provider)) {
+                return Ok(entry.body.clone());
+            }
+            let headers = request.headers_mut();
+            for (name, value) in [
+                (IF_NONE_MATCH, &entry.etag),
+                (IF_MODIFIED_SINCE, &entry.last_modified),
+            ] {
+                if let Some(value) = value.as_deref().and_then(|x| HeaderValue::from_str(x).ok()) {
+                    headers.insert(name, value);
+                }
+            }
+        }
+
+        let _permit = self.limiter.acquire(&url).await;
+        let response = self.execute(provider, request).await?;
+        let entry = match cached {
+            Some(entry) if response.status() == StatusCode::NOT_MODIFIED => CacheEntry {
+                fetched_at: now(),
+                ..entry
+            },
+            _ => {
+                let header = |name| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|x: &HeaderValue| x.to_str().ok())
+                        .map(str::to_string)
+                };
+                CacheEntry {
+                    etag: header(ETAG),
+                    last_modified: header(LAST_MODIFIED),
+                    fetched_at: now(),
+                    body: text(provider, response).await?,
+                    url,
+                }
+            }
+        };
+        cache.put(&entry).await?;
+        Ok(entry.body)
+    }
+
+    async fn execute(&self, provider: &str, request: Request) -> Result<Response, ProviderError> {
+        let url = request.url().to_string();
+        if self.offline {
+            return Err(ProviderError::Offline { url });
+        }
+        let execute = |request| async {
+            match self.inner.execute(request).await {
+                Ok(response) => check_status(provider, &url, response),
+                Err(source) => Err(ProviderError::Request {
+                    provider: provider.to_string(),
+                    url: url.clone(),
+                    source,
+                }),
+            }
+        };
+
+        let mut attempt = 0;
+        loop {
+            // a streamed body can't be sent twice
+            let Some(copy) = request.try_clone() else {
+                return execute(request).await;
+            };
+            match execute(copy).await {
+                Err(e) if attempt < self.retries && e.is_retryable() => {
+                    tokio::time::sleep(delay(attempt, &e)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+fn build(provider: &str, request: RequestBuilder) -> Result<Request, ProviderError> {
+    request.build().map_err(|source| ProviderError::Request {
+        provider: provider.to_string(),
+        url: source.url().map(|x| x.to_string()).unwrap_or_default(),
+        source,
+    })
+}
+
+async fn text(provider: &str, response: Response) -> Result<String, ProviderError> {
+    let url = response.url().to_string();
+    response
+        .text()
+        .await
+        .map_err(|source| ProviderError::Request {
+            provider: provider.to_string(),
+            url,
+            source,
+        })
+}
+
+/// `TOwInOK/Prisma/<version> UID: <machine id>`
+fn default_user_agent() -> String {
+    format!(
+        "TOwInOK/Prisma/{} UID: {}",
+        env!("CARGO_PKG_VERSION"),
+        machine_id()
+    )
+}
+
+/// Machine id, or the host name in containers without `/etc/machine-id`
+fn machine_id() -> String {
+    machine_uid::get()
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .filter(|x| !x.trim().is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Maps error statuses to errors, `304` is an answer to a conditional request
+fn check_status(provider: &str, url: &str, response: Response) -> Result<Response, ProviderError> {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(ProviderError::RateLimited {
+            provider: provider.to_string(),
+            retry_after: response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|x| x.to_str().ok())
+                .and_then(|x| x.parse().ok()),
+            url: url.to_string(),
+        });
+    }
+    if !status.is_success() && status != StatusCode::NOT_MODIFIED {
+        return Err(ProviderError::Status {
+            provider: provider.to_string(),
+            url: url.to_string(),
+            status: status.as_u16(),
+        });
+    }
+    Ok(response)
+}
+
+/// Exponential backoff with full jitter, `Retry-After` is used as the lower bound
+fn delay(attempt: u32, error: &ProviderError) -> Duration {
+    let ceiling = BACKOFF
+        .saturating_mul(1 << attempt.min(16))
+        .min(MAX_BACKOFF);
+    let delay = Duration::from_millis(fastrand::u64(..=ceiling.as_millis() as u64));
+    match error {
+        ProviderError::RateLimited {
+            retry_after: Some(seconds),
+            ..
+        } => delay.max(Duration::from_secs(*seconds)).min(MAX_BACKOFF),
+        _ => delay,
+    }
+}
@@ -0,0 +1,113 @@
+use prisma_core::platform::Platform;
+
+/// Errors of resolving items against provider APIs
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    /// Request couldn't be sent or the connection failed
+    #[error("{provider}: request to {url} failed: {source}")]
+    Request {
+        provider: String,
+        url: String,
+        source: reqwest::Error,
+    },
+    /// Server answered with an error status
+    #[error("{provider}: {url} returned HTTP {status}")]
+    Status {
+        provider: String,
+        url: String,
+        status: u16,
+    },
+    /// Server answered with HTTP 429
+    #[error("{provider}: rate limited on {url}")]
+    RateLimited {
+        provider: String,
+        url: String,
+        /// Seconds from the `Retry-After` header
+        retry_after: Option<u64>,
+    },
+    /// Response body isn't what the provider is expected to return
+    #[error("{provider}: invalid response from {url}: {source}")]
+    Decode {
+        provider: String,
+        url: String,
+        source: serde_json::Error,
+    },
+    /// Provider has no versions at all
+    #[error("{provider}: no versions available")]
+    NoVersions { provider: String },
+    /// Requested game version doesn't exist
+    #[error("{provider}: version {version} not found")]
+    VersionNotFound { provider: String, version: String },
+    /// Requested build doesn't exist for the game version
+    #[error("{provider}: build {build} of version {version} not found")]
+    BuildNotFound {
+        provider: String,
+        version: String,
+        build: String,
+    },
+    /// Requested loader or installer doesn't exist for the game version
+    #[error("{provider}: {component} {version} not found for {game_version}")]
+    LoaderNotFound {
+        provider: String,
+        /// `loader` or `installer`
+        component: String,
+        version: String,
+        game_version: String,
+    },
+    /// Extension doesn't exist or has no matching version
+    #[error("{provider}: extension {name} not found")]
+    ExtensionNotFound { provider: String, name: String },
+    /// Version was found but has no file to download
+    #[error("{provider}: no download for {name}")]
+    NoDownload { provider: String, name: String },
+    /// Game version is older than the provider supports
+    #[error("{provider}: game version {version} is not supported, {minimum} or newer is required")]
+    UnsupportedVersion {
+        provider: String,
+        version: String,
+        minimum: String,
+    },
+    /// Platform has no provider
+    #[error("{platform} is not supported yet")]
+    Unsupported { platform: Platform },
+    /// Nothing is registered under the name
+    #[error("no {kind} provider registered as {name}")]
+    NotRegistered {
+        /// `core` or `extension`
+        kind: String,
+        name: String,
+    },
+    /// HTTP client couldn't be built from the network options
+    #[error("invalid network options: {0}")]
+    Client(reqwest::Error),
+    /// Certificate file couldn't be read
+    #[error("{}: {source}", path.display())]
+    Certificate {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    /// Offline and the answer isn't in the cache, `what` is the item or the URL
+    #[error("not cached: {what}")]
+    NotCached { what: String },
+    /// Offline and the request needs the network
+    #[error("offline: {url} can't be downloaded")]
+    Offline { url: String },
+    /// Cache entry couldn't be written
+    #[error("cache {}: {source}", path.display())]
+    Cache {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl ProviderError {
+    /// Error is temporary and the request may succeed if retried
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProviderError::Request { source, .. } => source.is_timeout() || source.is_connect(),
+            ProviderError::Status { status, .. } => *status >= 500,
+            ProviderError::RateLimited { .. } => true,
+            _ => false,
+        }
+    }
+}
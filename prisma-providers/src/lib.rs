@@ -1,13 +1,25 @@
-use prisma_core::{
-    extension::{ExtensionProvider, ExtensionType},
-    item::Item,
-    platform::Platform,
-    provider::Provider,
-};
+use std::future::Future;
+
+use prisma_core::item::Item;
 use prisma_hash::HashType;
-use providers::{modrinth::ModrinthData, papermc::PaperMC, purpur::Purpur, vanilla::Vanilla};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+pub mod cache;
+pub mod client;
+pub mod error;
+pub mod limit;
+pub mod provider;
 pub mod providers;
+pub mod registry;
+pub use async_trait::async_trait;
+pub use cache::Cache;
+pub use client::Client;
+pub use error::ProviderError;
+pub use limit::Limiter;
+pub use provider::Provider;
+pub use registry::Registry;
+
+#[derive(Debug, Clone)]
 pub struct DownloadMeta {
     /// link to download jar file
     pub download_link: String,
@@ -21,39 +33,191 @@ pub struct DownloadMeta {
     ///
     /// like lastest build if minecraft version
     pub version_build: Option<String>,
+    /// mod loader version, if the core has a loader
+    pub loader_version: Option<String>,
+    /// loader installer version, if the core has an installer
+    pub installer_version: Option<String>,
+    /// how to start the core if it isn't a plain `java -jar`
+    pub launch: Option<Launch>,
+    /// how to compile the core if the link is a build tool
+    pub build: Option<Build>,
+    /// base URL of the provider that answered, set when resolved through a mirror list
+    pub base: Option<String>,
+}
+
+/// Core compiled locally by the downloaded build tool, like Spigot with BuildTools
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Build {
+    /// Name of the built jar, unique per revision, like `spigot-1.20.1-4077`
+    pub name: String,
+    /// Arguments of `java -jar <tool>`, [Build::OUTPUT_DIR] is replaced with the output directory
+    pub args: Vec<String>,
+}
+
+impl Build {
+    /// Placeholder of the output directory in `args`
+    pub const OUTPUT_DIR: &'static str = "${output_dir}";
+}
+
+/// Start of a core that isn't a plain `java -jar`
+///
+/// - with `main_class` the libraries are the classpath, the downloaded file is the game jar
+///   passed to the loader by `jvm_args`
+/// - without it `jvm_args` start the server on their own, like Forge `@unix_args.txt`
+/// - with `install_args` the downloaded file is an installer that produces the libraries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Launch {
+    /// Class to start
+    pub main_class: Option<String>,
+    /// Extra JVM arguments, [Launch::CORE_JAR] is replaced with the core jar path
+    pub jvm_args: Vec<String>,
+    /// Classpath, in order
+    pub libraries: Vec<Library>,
+    /// Arguments of `java -jar <installer>`, run once in an empty directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub install_args: Vec<String>,
+}
+
+impl Launch {
+    /// Placeholder of the core jar path in `jvm_args`
+    pub const CORE_JAR: &'static str = "${core_jar}";
+}
+
+/// Maven artifact needed to start a core
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Library {
+    /// Maven coordinates, like `org.quiltmc:quilt-loader:0.26.0`
+    pub name: String,
+    /// link to download jar file
+    pub url: String,
+    /// hash of this file
+    pub hash: HashType,
+}
+
+impl Library {
+    /// Path in a maven repository, like `org/quiltmc/quilt-loader/0.26.0/quilt-loader-0.26.0.jar`
+    ///
+    /// Returns `None` if the name isn't `group:artifact:version[:classifier]`.
+    pub fn path(name: &str) -> Option<String> {
+        let mut parts = name.split(':');
+        let (group, artifact, version) = (parts.next()?, parts.next()?, parts.next()?);
+        let classifier = parts.next().map(|x| format!("-{}", x)).unwrap_or_default();
+        if parts.next().is_some() || [group, artifact, version].iter().any(|x| x.is_empty()) {
+            return None;
+        }
+        Some(format!(
+            "{}/{}/{}/{}-{}{}.jar",
+            group.replace('.', "/"),
+            artifact,
+            version,
+            artifact,
+            version,
+            classifier
+        ))
+    }
 }
 
 impl DownloadMeta {
-    pub async fn fetch(
-        item: &Item,
-    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync + 'static>> {
-        match &item.provider {
-            Provider::Core(platform) => match platform {
-                Platform::Vanilla => Vanilla::get_link(item).await,
-                Platform::Spigot => unimplemented!("Need to implement for Spigot platform"),
-                Platform::Bukkit => unimplemented!("Need to implement for Bukkit platform"),
-                Platform::Paper => PaperMC::get_link(item).await,
-                Platform::Folia => PaperMC::get_link(item).await,
-                Platform::Waterfall => PaperMC::get_link(item).await,
-                Platform::Velocity => PaperMC::get_link(item).await,
-                Platform::Purpur => Purpur::get_link(item).await,
-                Platform::Fabric => unimplemented!("Need to implement for Fabric platform"),
-                Platform::Quilt => unimplemented!("Need to implement for Quilt platform"),
-                Platform::Forge => unimplemented!("Need to implement for Forge platform"),
-                Platform::NeoForge => unimplemented!("Need to implement for NeoForge platform"),
-            },
-            Provider::Extension((name, platform, extension_type)) => match extension_type {
-                ExtensionType::Mod(extension_provider) => match extension_provider {
-                    ExtensionProvider::Modrinth => {
-                        ModrinthData::get_link(name, platform, item).await
-                    }
-                },
-                ExtensionType::Plugin(extension_provider) => match extension_provider {
-                    ExtensionProvider::Modrinth => {
-                        ModrinthData::get_link(name, platform, item).await
-                    }
-                },
+    /// Resolves the item with the provider registered for it in [Registry::global]
+    pub async fn fetch(item: &Item) -> Result<Self, ProviderError> {
+        Registry::global().resolve(item).await
+    }
+}
+
+/// Runs `request` with the base URLs of the provider in order, see [Client::endpoints]
+///
+/// The next base is tried only if the previous one is unreachable. Returns the answer
+/// and the base that gave it.
+pub(crate) async fn mirrored<T, F, Fut>(
+    provider: &str,
+    default: &str,
+    request: F,
+) -> Result<(T, String), ProviderError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, ProviderError>>,
+{
+    let mut bases = Client::global()?.endpoints(provider, default).into_iter();
+    let mut base = bases.next().unwrap_or_else(|| default.to_string());
+    loop {
+        match request(base.clone()).await {
+            Ok(value) => return Ok((value, base)),
+            Err(e) if e.is_retryable() => match bases.next() {
+                Some(next) => base = next,
+                None => return Err(e),
             },
+            Err(e) => return Err(e),
         }
     }
 }
+
+/// Moves an absolute link given by the upstream `default` onto `base`, see [mirrored]
+///
+/// A mirror serves the upstream files at the same paths under its root, which is the
+/// base without the path of `default`. Links are kept as they are for `default`.
+pub(crate) fn rebase(url: &str, base: &str, default: &str) -> String {
+    let default = default.trim_end_matches('/');
+    let Ok(link) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    if base.trim_end_matches('/') == default {
+        return url.to_string();
+    }
+    let path = reqwest::Url::parse(default)
+        .map(|x| x.path().trim_end_matches('/').to_string())
+        .unwrap_or_default();
+    let root = base.strip_suffix(path.as_str()).unwrap_or(base);
+    match link.query() {
+        Some(query) => format!("{}{}?{}", root.trim_end_matches('/'), link.path(), query),
+        None => format!("{}{}", root.trim_end_matches('/'), link.path()),
+    }
+}
+
+/// Resolves with [mirrored] and records the base in the answer
+pub(crate) async fn resolve_mirrored<F, Fut>(
+    provider: &str,
+    default: &str,
+    resolve: F,
+) -> Result<DownloadMeta, ProviderError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<DownloadMeta, ProviderError>>,
+{
+    let (meta, base) = mirrored(provider, default, resolve).await?;
+    Ok(DownloadMeta {
+        base: Some(base),
+        ..meta
+    })
+}
+
+/// Fetches with the global [Client] and decodes the JSON answer
+pub(crate) async fn send_json<T: DeserializeOwned>(
+    provider: &str,
+    request: reqwest::RequestBuilder,
+) -> Result<T, ProviderError> {
+    let url = request
+        .try_clone()
+        .and_then(|x| x.build().ok())
+        .map(|x| x.url().to_string())
+        .unwrap_or_default();
+    let body = Client::global()?.fetch(provider, request).await?;
+    serde_json::from_str(&body).map_err(|source| ProviderError::Decode {
+        provider: provider.to_string(),
+        url,
+        source,
+    })
+}
+
+/// Fetches a GET request and decodes the JSON answer
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    provider: &str,
+    url: &str,
+) -> Result<T, ProviderError> {
+    send_json(provider, Client::global()?.get(url)).await
+}
+
+/// Fetches a GET request and returns the body as text
+pub(crate) async fn get_text(provider: &str, url: &str) -> Result<String, ProviderError> {
+    let client = Client::global()?;
+    client.fetch(provider, client.get(url)).await
+}
@@ -0,0 +1,71 @@
+//! Limits of concurrent requests, for every host and for each one.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Bounds the requests in flight, clones share the limits
+#[derive(Debug, Clone)]
+pub struct Limiter {
+    global: Arc<Semaphore>,
+    per_host: usize,
+    hosts: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+/// Slot of a request, given back on drop
+#[derive(Debug)]
+pub struct Permit {
+    _host: OwnedSemaphorePermit,
+    _global: OwnedSemaphorePermit,
+}
+
+impl Limiter {
+    /// At most `global` requests at once and `per_host` to the same host, `0` is unlimited
+    pub fn new(global: usize, per_host: usize) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(limit(global))),
+            per_host: limit(per_host),
+            hosts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Waits for a slot to request `url`
+    pub async fn acquire(&self, url: &str) -> Permit {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|x| x.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let semaphore = self
+            .hosts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_host)))
+            .clone();
+
+        // the host slot first, a request waiting for a busy host doesn't hold a global one
+        let host = semaphore
+            .acquire_owned()
+            .await
+            .expect("host semaphore is never closed");
+        let global = self
+            .global
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("global semaphore is never closed");
+        Permit {
+            _host: host,
+            _global: global,
+        }
+    }
+}
+
+fn limit(value: usize) -> usize {
+    match value {
+        0 => Semaphore::MAX_PERMITS,
+        value => value,
+    }
+}
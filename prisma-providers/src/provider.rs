@@ -0,0 +1,38 @@
+//! Extension point for sources of cores and extensions.
+use prisma_core::item::Item;
+
+use crate::{Client, DownloadMeta, ProviderError};
+
+/// Source of cores or extensions, registered in a [Registry](crate::Registry)
+///
+/// Implement it with [async_trait](crate::async_trait):
+///
+/// ```ignore
+/// #[prisma_providers::async_trait]
+/// impl Provider for Inhouse {
+///     fn name(&self) -> &str {
+///         "inhouse"
+///     }
+///     async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> { .. }
+///     async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> { .. }
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait Provider: Send + Sync {
+    /// Name used in errors, like `papermc`
+    fn name(&self) -> &str;
+
+    /// Resolves the item to a file to download
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError>;
+
+    /// Versions available for the item, newest first
+    ///
+    /// Game versions for cores, provider versions for extensions.
+    async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError>;
+
+    /// Starts the download of a resolved file
+    async fn download(&self, url: &str) -> Result<reqwest::Response, ProviderError> {
+        let client = Client::global()?;
+        client.send(self.name(), client.get(url)).await
+    }
+}
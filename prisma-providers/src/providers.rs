@@ -1,4 +1,8 @@
+pub mod fabric;
+pub mod forge;
 pub mod modrinth;
 pub mod papermc;
 pub mod purpur;
+pub mod quilt;
+pub mod spigot;
 pub mod vanilla;
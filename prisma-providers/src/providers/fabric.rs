@@ -0,0 +1,180 @@
+use prisma_core::item::Item;
+use prisma_hash::HashType;
+use serde::{Deserialize, Serialize};
+
+use crate::{get_json, mirrored, resolve_mirrored, DownloadMeta, Provider, ProviderError};
+
+const PROVIDER: &str = "fabric";
+const META: &str = "https://meta.fabricmc.net/v2";
+
+/// Fabric server launcher from meta.fabricmc.net
+///
+/// The launcher jar is built by the meta for the game, loader and installer versions,
+/// it downloads the game and libraries on the first start.
+pub struct Fabric;
+
+/// Game version from `/versions/game`, the Quilt meta serves the same list
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct GameVersion {
+    version: String,
+    stable: bool,
+}
+
+/// Loader compatible with a game version, from `/versions/loader/<game>`
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LoaderEntry {
+    loader: Component,
+}
+
+/// Loader or installer version
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Component {
+    version: String,
+    stable: bool,
+}
+
+impl Fabric {
+    /// Resolves game, loader and installer versions and the launcher link
+    ///
+    /// Unpinned versions resolve to the latest stable one, or to the latest one
+    /// for the `Beta` channel.
+    pub async fn get_link(item: &Item) -> Result<DownloadMeta, ProviderError> {
+        resolve_mirrored(PROVIDER, META, |base| Self::get_link_at(base, item)).await
+    }
+
+    /// Resolves against `base`, [META] or a mirror of it
+    async fn get_link_at(base: String, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let version = &item.version;
+        let unstable = version.channel.is_beta();
+
+        let game_version =
+            find_game_version(PROVIDER, &base, version.game_version.as_deref(), unstable).await?;
+
+        let loaders: Vec<LoaderEntry> = get_json(
+            PROVIDER,
+            &format!("{}/versions/loader/{}", base, game_version),
+        )
+        .await?;
+        let loader_version = pick(
+            loaders.into_iter().map(|x| x.loader).collect(),
+            version.loader_version.as_deref(),
+            unstable,
+        )
+        .ok_or_else(|| not_found("loader", version.loader_version.as_ref(), &game_version))?;
+
+        let installers: Vec<Component> =
+            get_json(PROVIDER, &format!("{}/versions/installer", base)).await?;
+        let installer_version = pick(installers, version.installer_version.as_deref(), unstable)
+            .ok_or_else(|| {
+                not_found(
+                    "installer",
+                    version.installer_version.as_ref(),
+                    &game_version,
+                )
+            })?;
+
+        Ok(DownloadMeta {
+            download_link: format!(
+                "{}/versions/loader/{}/{}/{}/server/jar",
+                base, game_version, loader_version, installer_version
+            ),
+            // meta doesn't publish hashes of the launcher
+            hash: HashType::None,
+            game_version,
+            version_build: None,
+            loader_version: Some(loader_version),
+            installer_version: Some(installer_version),
+            launch: None,
+            build: None,
+            base: None,
+        })
+    }
+}
+
+/// Game versions of a Fabric style meta at `base`, newest first
+pub(crate) async fn game_versions(
+    provider: &str,
+    base: &str,
+) -> Result<Vec<GameVersion>, ProviderError> {
+    get_json(provider, &format!("{}/versions/game", base)).await
+}
+
+/// Pinned game version if the meta has it, otherwise the newest (stable) one
+pub(crate) async fn find_game_version(
+    provider: &str,
+    base: &str,
+    version: Option<&str>,
+    unstable: bool,
+) -> Result<String, ProviderError> {
+    let versions = game_versions(provider, base).await?;
+    match version {
+        Some(version) => versions
+            .into_iter()
+            .find(|x| x.version == version)
+            .map(|x| x.version)
+            .ok_or_else(|| ProviderError::VersionNotFound {
+                provider: provider.to_string(),
+                version: version.to_string(),
+            }),
+        // newest first
+        None => versions
+            .into_iter()
+            .find(|x| x.stable || unstable)
+            .map(|x| x.version)
+            .ok_or_else(|| ProviderError::NoVersions {
+                provider: provider.to_string(),
+            }),
+    }
+}
+
+/// Game versions for the `versions` of a provider, stable ones unless `unstable`
+pub(crate) async fn list_game_versions(
+    provider: &str,
+    meta: &str,
+    unstable: bool,
+) -> Result<Vec<String>, ProviderError> {
+    let (versions, _) = mirrored(provider, meta, |base| async move {
+        game_versions(provider, &base).await
+    })
+    .await?;
+    Ok(versions
+        .into_iter()
+        .filter(|x| x.stable || unstable)
+        .map(|x| x.version)
+        .collect())
+}
+
+/// Pinned version if it exists, otherwise the newest (stable) one
+fn pick(components: Vec<Component>, pinned: Option<&str>, unstable: bool) -> Option<String> {
+    components
+        .into_iter()
+        .find(|x| match pinned {
+            Some(pinned) => x.version == pinned,
+            None => x.stable || unstable,
+        })
+        .map(|x| x.version)
+}
+
+fn not_found(component: &str, pinned: Option<&String>, game_version: &str) -> ProviderError {
+    ProviderError::LoaderNotFound {
+        provider: PROVIDER.to_string(),
+        component: component.to_string(),
+        version: pinned.cloned().unwrap_or_else(|| "latest".to_string()),
+        game_version: game_version.to_string(),
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for Fabric {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        Fabric::get_link(item).await
+    }
+
+    async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> {
+        list_game_versions(PROVIDER, META, item.version.channel.is_beta()).await
+    }
+}
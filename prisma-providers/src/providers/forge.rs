@@ -0,0 +1,256 @@
+use prisma_core::{item::Item, platform::Platform, provider::Provider as Source};
+use prisma_hash::HashType;
+
+use crate::{get_text, mirrored, resolve_mirrored, DownloadMeta, Launch, Provider, ProviderError};
+
+/// Forge and NeoForge installers from their maven repositories
+///
+/// The downloaded file is the installer, it is run with `--installServer` and produces
+/// the libraries and the `unix_args.txt` used to start the server.
+/// Only the args file layout of Minecraft 1.17+ is supported, see [MINIMUM].
+pub struct Forge;
+
+/// Oldest game version started with an args file
+const MINIMUM: &str = "1.17";
+
+/// Maven repository of a loader
+struct Repository {
+    provider: &'static str,
+    /// Artifact directory, like `https://maven.neoforged.net/releases/net/neoforged/neoforge`
+    base: &'static str,
+    /// Artifact path under `libraries`
+    group_path: &'static str,
+    artifact: &'static str,
+}
+
+const FORGE: Repository = Repository {
+    provider: "forge",
+    base: "https://maven.minecraftforge.net/net/minecraftforge/forge",
+    group_path: "net/minecraftforge/forge",
+    artifact: "forge",
+};
+
+const NEOFORGE: Repository = Repository {
+    provider: "neoforge",
+    base: "https://maven.neoforged.net/releases/net/neoforged/neoforge",
+    group_path: "net/neoforged/neoforge",
+    artifact: "neoforge",
+};
+
+/// Loader release for a game version
+#[derive(Debug, Clone, PartialEq)]
+struct Release {
+    game_version: String,
+    loader_version: String,
+    /// Version in the maven repository
+    maven_version: String,
+}
+
+impl Forge {
+    /// Resolves game and loader versions from the maven metadata and the installer link
+    ///
+    /// Unpinned versions resolve to the newest one, NeoForge betas are only used
+    /// on the `Beta` channel.
+    pub async fn get_link(item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let repository = Repository::of(item)?;
+        if let Some(game_version) = &item.version.game_version {
+            if !is_supported(game_version) {
+                return Err(ProviderError::UnsupportedVersion {
+                    provider: repository.provider.to_string(),
+                    version: game_version.clone(),
+                    minimum: MINIMUM.to_string(),
+                });
+            }
+        }
+        resolve_mirrored(repository.provider, repository.base, |base| {
+            Self::get_link_at(base, repository, item)
+        })
+        .await
+    }
+
+    /// Resolves against `base`, the artifact directory of the repository or a mirror of it
+    async fn get_link_at(
+        base: String,
+        repository: &Repository,
+        item: &Item,
+    ) -> Result<DownloadMeta, ProviderError> {
+        let provider = repository.provider;
+        let version = &item.version;
+        let unstable = version.channel.is_beta();
+
+        let metadata = get_text(provider, &format!("{}/maven-metadata.xml", base)).await?;
+        let releases = maven_versions(&metadata)
+            .filter_map(|x| repository.release(x))
+            .filter(|x| is_supported(&x.game_version))
+            .filter(|x| unstable || version.loader_version.is_some() || !x.is_beta())
+            .collect::<Vec<_>>();
+
+        let game_version = match &version.game_version {
+            Some(game_version) => releases
+                .iter()
+                .find(|x| &x.game_version == game_version)
+                .map(|x| x.game_version.clone())
+                .ok_or_else(|| ProviderError::VersionNotFound {
+                    provider: provider.to_string(),
+                    version: game_version.clone(),
+                })?,
+            None => releases
+                .iter()
+                .max_by(|a, b| numeric(&a.game_version).cmp(&numeric(&b.game_version)))
+                .map(|x| x.game_version.clone())
+                .ok_or_else(|| ProviderError::NoVersions {
+                    provider: provider.to_string(),
+                })?,
+        };
+
+        let release = releases
+            .into_iter()
+            .filter(|x| x.game_version == game_version)
+            .filter(|x| match &version.loader_version {
+                Some(pinned) => &x.loader_version == pinned,
+                None => true,
+            })
+            .max_by(|a, b| numeric(&a.loader_version).cmp(&numeric(&b.loader_version)))
+            .ok_or_else(|| ProviderError::LoaderNotFound {
+                provider: provider.to_string(),
+                component: "loader".to_string(),
+                version: version
+                    .loader_version
+                    .clone()
+                    .unwrap_or_else(|| "latest".to_string()),
+                game_version: game_version.clone(),
+            })?;
+
+        let installer = format!(
+            "{base}/{version}/{artifact}-{version}-installer.jar",
+            version = release.maven_version,
+            artifact = repository.artifact,
+        );
+        let sha1 = get_text(provider, &format!("{}.sha1", installer)).await?;
+        let args_file = if cfg!(windows) {
+            "win_args.txt"
+        } else {
+            "unix_args.txt"
+        };
+
+        Ok(DownloadMeta {
+            hash: HashType::new_sha1(
+                sha1.split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            download_link: installer,
+            game_version,
+            version_build: None,
+            loader_version: Some(release.loader_version),
+            installer_version: None,
+            launch: Some(Launch {
+                main_class: None,
+                jvm_args: vec![format!(
+                    "@libraries/{}/{}/{}",
+                    repository.group_path, release.maven_version, args_file
+                )],
+                libraries: vec![],
+                install_args: vec!["--installServer".to_string()],
+            }),
+            build: None,
+            base: None,
+        })
+    }
+}
+
+impl Repository {
+    fn of(item: &Item) -> Result<&'static Repository, ProviderError> {
+        match &item.provider {
+            Source::Core(Platform::Forge) => Ok(&FORGE),
+            Source::Core(Platform::NeoForge) => Ok(&NEOFORGE),
+            Source::Core(platform) | Source::Extension((_, platform, _)) => {
+                Err(ProviderError::Unsupported {
+                    platform: platform.clone(),
+                })
+            }
+        }
+    }
+
+    /// Splits a maven version into game and loader versions
+    ///
+    /// - Forge: `1.20.1-47.2.0`
+    /// - NeoForge: `20.4.237` is `1.20.4`, `21.0.1-beta` is `1.21`
+    fn release(&self, maven_version: &str) -> Option<Release> {
+        let (game_version, loader_version) = if self.provider == FORGE.provider {
+            let (game, loader) = maven_version.split_once('-')?;
+            (game.to_string(), loader.to_string())
+        } else {
+            let mut parts = maven_version.split('.');
+            let (major, minor) = (parts.next()?, parts.next()?);
+            let game = match minor {
+                "0" => format!("1.{}", major),
+                minor => format!("1.{}.{}", major, minor),
+            };
+            (game, maven_version.to_string())
+        };
+        Some(Release {
+            game_version,
+            loader_version,
+            maven_version: maven_version.to_string(),
+        })
+    }
+}
+
+impl Release {
+    fn is_beta(&self) -> bool {
+        self.loader_version.contains("beta") || self.loader_version.contains("alpha")
+    }
+}
+
+/// Values of `<version>` tags in a `maven-metadata.xml`
+fn maven_versions(metadata: &str) -> impl Iterator<Item = &str> {
+    metadata
+        .split("<version>")
+        .skip(1)
+        .filter_map(|x| x.split_once("</version>"))
+        .map(|x| x.0.trim())
+}
+
+/// Whether the game version is [MINIMUM] or newer
+fn is_supported(game_version: &str) -> bool {
+    numeric(game_version) >= numeric(MINIMUM)
+}
+
+/// Numeric parts of a version, `1.20.1-47.2.0` is `[1, 20, 1, 47, 2, 0]`
+fn numeric(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-'])
+        .map_while(|x| x.parse().ok())
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl Provider for Forge {
+    fn name(&self) -> &str {
+        "forge"
+    }
+
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        Forge::get_link(item).await
+    }
+
+    /// Supported game versions with a loader release, newest first
+    async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> {
+        let repository = Repository::of(item)?;
+        let (metadata, _) = mirrored(repository.provider, repository.base, |base| async move {
+            get_text(repository.provider, &format!("{}/maven-metadata.xml", base)).await
+        })
+        .await?;
+        let mut versions = maven_versions(&metadata)
+            .filter_map(|x| repository.release(x))
+            .filter(|x| is_supported(&x.game_version))
+            .filter(|x| item.version.channel.is_beta() || !x.is_beta())
+            .map(|x| x.game_version)
+            .collect::<Vec<_>>();
+        versions.sort_by_key(|x| std::cmp::Reverse(numeric(x)));
+        versions.dedup();
+        Ok(versions)
+    }
+}
@@ -1,8 +1,14 @@
-use prisma_core::{channel::Channel, item::Item, platform::Platform};
+use prisma_core::{channel::Channel, item::Item, platform::Platform, provider::Provider as Source};
 use prisma_hash::HashType;
 use serde::{Deserialize, Serialize};
 
-use crate::DownloadMeta;
+use crate::{mirrored, rebase, send_json, Client, DownloadMeta, Provider, ProviderError};
+
+const PROVIDER: &str = "modrinth";
+const BASE: &str = "https://api.modrinth.com/v2";
+
+/// Extensions from the Modrinth API, registered as `modrinth`
+pub struct Modrinth;
 
 /// # Example
 /// we have cdn like this: `https://cdn.modrinth.com/data/PROJECT_ID/versions/ID/NAME-platform-VERSION.jar`
@@ -13,6 +19,9 @@ pub struct ModrinthData {
     game_versions: Vec<String>,
     //Always change ich version
     id: String,
+    /// Version of the extension, like `2.7.1`
+    #[serde(default)]
+    version_number: String,
     //Stable token.
     // project_id: String,
     files: Vec<File>,
@@ -31,6 +40,7 @@ struct Hashes {
     sha512: String,
 }
 
+#[allow(dead_code)]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct Dependency {
     project_id: String,
@@ -42,55 +52,123 @@ impl ModrinthData {
         name: &String,
         platform: &Platform,
         item: &Item,
-    ) -> Result<DownloadMeta, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    ) -> Result<DownloadMeta, ProviderError> {
+        let (modrinth_data, base) = mirrored(PROVIDER, BASE, |base| {
+            Self::list(base, name, platform, item)
+        })
+        .await?;
+
+        let modrinth_data =
+            modrinth_data
+                .first()
+                .ok_or_else(|| ProviderError::ExtensionNotFound {
+                    provider: PROVIDER.to_string(),
+                    name: name.to_string(),
+                })?;
+
+        let version = modrinth_data
+            .game_versions
+            .first()
+            .ok_or_else(|| ProviderError::NoVersions {
+                provider: PROVIDER.to_string(),
+            })?
+            .to_string();
+
+        modrinth_data
+            .files
+            .first()
+            .map(|x| DownloadMeta {
+                // files are on the CDN, mirrors serve them as well
+                download_link: rebase(&x.url, &base, BASE),
+                hash: HashType::new_sha1(x.hashes.sha1.to_string()),
+                game_version: version,
+                version_build: Some(modrinth_data.id.to_owned()),
+                loader_version: None,
+                installer_version: None,
+                launch: None,
+                build: None,
+                base: Some(base),
+            })
+            .ok_or_else(|| ProviderError::NoDownload {
+                provider: PROVIDER.to_string(),
+                name: name.to_string(),
+            })
+    }
+
+    /// Featured versions of an extension for the platform and game version, newest first
+    async fn list(
+        base: String,
+        name: &String,
+        platform: &Platform,
+        item: &Item,
+    ) -> Result<Vec<ModrinthData>, ProviderError> {
         let channel = match &item.version.version_build {
             Some(channel) => channel.to_string(),
             None => Channel::Release.to_string(),
         };
 
-        let link = format!("https://api.modrinth.com/v2/project/{}/version", name);
+        let link = format!("{}/project/{}/version", base, name);
+
+        // Quilt loads Fabric mods as well
+        let loaders = match platform {
+            Platform::Quilt => "[\"quilt\",\"fabric\"]".to_string(),
+            platform => format!("[\"{}\"]", platform.as_ref().to_lowercase()),
+        };
 
         let query = {
             match &item.version.game_version {
                 Some(game_version) => vec![
                     ("game_version", format!("[\"{}\"]", game_version)),
-                    ("loaders", format!("[\"{}\"]", platform)),
+                    ("loaders", loaders),
                     ("featured", true.to_string()),
                     ("version_type", channel),
                 ],
                 None => vec![
-                    ("loaders", format!("[\"{}\"]", platform)),
+                    ("loaders", loaders),
                     ("featured", true.to_string()),
                     ("version_type", channel),
                 ],
             }
         };
-        let user_agent = format!("TOwInOK/Prisma UID: {}", machine_uid::get().unwrap());
-        let client = reqwest::Client::builder().user_agent(user_agent).build()?;
+        match send_json(PROVIDER, Client::global()?.get(&link).query(&query)).await {
+            Err(ProviderError::Status { status: 404, .. }) => {
+                Err(ProviderError::ExtensionNotFound {
+                    provider: PROVIDER.to_string(),
+                    name: name.to_string(),
+                })
+            }
+            result => result,
+        }
+    }
+}
 
-        let modrinth_data: Vec<ModrinthData> =
-            client.get(&link).query(&query).send().await?.json().await?;
+/// Name and platform of an extension item
+fn extension(item: &Item) -> Result<(&String, &Platform), ProviderError> {
+    match &item.provider {
+        Source::Extension((name, platform, _)) => Ok((name, platform)),
+        Source::Core(platform) => Err(ProviderError::Unsupported {
+            platform: platform.clone(),
+        }),
+    }
+}
 
-        let modrinth_data = match modrinth_data.first() {
-            Some(e) => e,
-            None => Err(format!("Extension {} not found", name))?,
-        };
+#[async_trait::async_trait]
+impl Provider for Modrinth {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
 
-        let version = modrinth_data
-            .game_versions
-            .first()
-            .ok_or_else(|| format!("Not found any version of {}", modrinth_data.id))?
-            .to_string();
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let (name, platform) = extension(item)?;
+        ModrinthData::get_link(name, platform, item).await
+    }
 
-        modrinth_data
-            .files
-            .first()
-            .map(|x| DownloadMeta {
-                download_link: x.url.to_string(),
-                hash: HashType::new_sha1(x.hashes.sha1.to_string()),
-                game_version: version,
-                version_build: Some(modrinth_data.id.to_owned()),
-            })
-            .ok_or_else(|| format!("Download link for extension {} not found", name).into())
+    async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> {
+        let (name, platform) = extension(item)?;
+        let (list, _) = mirrored(PROVIDER, BASE, |base| {
+            ModrinthData::list(base, name, platform, item)
+        })
+        .await?;
+        Ok(list.into_iter().map(|x| x.version_number).collect())
     }
 }
@@ -1,8 +1,12 @@
-use prisma_core::{item::Item, provider::Provider};
+use prisma_core::{item::Item, provider::Provider as Source};
 use prisma_hash::HashType;
 use serde::{Deserialize, Serialize};
 
-use crate::DownloadMeta;
+use crate::{get_json, mirrored, resolve_mirrored, DownloadMeta, Provider, ProviderError};
+
+const PROVIDER: &str = "papermc";
+/// https://api.papermc.io/v2/projects/{Project}/versions/{Version}/builds/{Build}
+const BASE: &str = "https://api.papermc.io/v2";
 
 pub struct PaperMC;
 
@@ -33,44 +37,57 @@ struct Application {
 }
 
 async fn find_version(
+    base: &str,
     version: Option<&str>,
     core_name: &str,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let link = format!("https://api.papermc.io/v2/projects/{}", core_name);
-    let version_list = reqwest::get(link)
-        .await?
-        .json::<VersionList>()
-        .await?
-        .versions;
-    version
-        .and_then(|v| version_list.iter().find(|x| x == &v))
-        .or_else(|| version_list.last())
-        .map(|v| v.to_owned())
-        .ok_or_else(|| "No versions available".into())
+) -> Result<String, ProviderError> {
+    let link = format!("{}/projects/{}", base, core_name);
+    let version_list = get_json::<VersionList>(PROVIDER, &link).await?.versions;
+    match version {
+        Some(version) => version_list
+            .into_iter()
+            .find(|x| x == version)
+            .ok_or_else(|| ProviderError::VersionNotFound {
+                provider: PROVIDER.to_string(),
+                version: version.to_string(),
+            }),
+        None => version_list
+            .last()
+            .cloned()
+            .ok_or_else(|| ProviderError::NoVersions {
+                provider: PROVIDER.to_string(),
+            }),
+    }
+}
+
+/// Project name of the core, like `paper`
+fn project(item: &Item) -> Result<String, ProviderError> {
+    match &item.provider {
+        Source::Core(platform) => Ok(platform.key()),
+        Source::Extension((_, platform, _)) => Err(ProviderError::Unsupported {
+            platform: platform.clone(),
+        }),
+    }
 }
 
 impl PaperMC {
-    pub async fn get_link(
-        item: &Item,
-    ) -> Result<DownloadMeta, Box<dyn std::error::Error + Send + Sync + 'static>> {
-        let core_name = match &item.provider {
-            Provider::Core(platform) => platform.as_ref().to_lowercase(),
-            _ => panic!("Used unrichable type"),
-        };
+    pub async fn get_link(item: &Item) -> Result<DownloadMeta, ProviderError> {
+        resolve_mirrored(PROVIDER, BASE, |base| Self::get_link_at(base, item)).await
+    }
+
+    /// Resolves against `base`, [BASE] or a mirror of it
+    async fn get_link_at(base: String, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let core_name = project(item)?;
 
         let game_version = item.version.game_version.as_deref();
 
         // check on exist
-        let game_version = find_version(game_version, &core_name).await?;
+        let game_version = find_version(&base, game_version, &core_name).await?;
 
-        let version_build_link = format!(
-            "https://api.papermc.io/v2/projects/{}/versions/{}",
-            core_name, game_version
-        );
+        let version_build_link =
+            format!("{}/projects/{}/versions/{}", base, core_name, game_version);
 
-        let build_list = reqwest::get(version_build_link)
-            .await?
-            .json::<BuildList>()
+        let build_list = get_json::<BuildList>(PROVIDER, &version_build_link)
             .await?
             .builds;
 
@@ -80,12 +97,14 @@ impl PaperMC {
                     .iter()
                     .any(|x| *x == build.parse::<u16>().unwrap_or_default())
                 {
-                    return Err(
-                        format!("not found version {} with build {}", game_version, build).into(),
-                    );
+                    return Err(ProviderError::BuildNotFound {
+                        provider: PROVIDER.to_string(),
+                        version: game_version,
+                        build,
+                    });
                 }
 
-                let (buildlink, url) = gen_link(core_name, &game_version, &build).await?;
+                let (buildlink, url) = gen_link(&base, core_name, &game_version, &build).await?;
 
                 Ok(DownloadMeta {
                     download_link: format!(
@@ -95,11 +114,17 @@ impl PaperMC {
                     hash: HashType::new_sha256(url.downloads.application.sha256),
                     game_version,
                     version_build: Some(build),
+                    loader_version: None,
+                    installer_version: None,
+                    launch: None,
+                    build: None,
+                    base: None,
                 })
             }
             None => {
                 if let Some(last_build) = build_list.last().map(|x| x.to_string()) {
-                    let (buildlink, url) = gen_link(core_name, &game_version, &last_build).await?;
+                    let (buildlink, url) =
+                        gen_link(&base, core_name, &game_version, &last_build).await?;
 
                     Ok(DownloadMeta {
                         download_link: format!(
@@ -109,9 +134,17 @@ impl PaperMC {
                         hash: HashType::new_sha256(url.downloads.application.sha256),
                         game_version,
                         version_build: Some(last_build),
+                        loader_version: None,
+                        installer_version: None,
+                        launch: None,
+                        build: None,
+                        base: None,
                     })
                 } else {
-                    Err(format!("not found version {}", game_version).into())
+                    Err(ProviderError::VersionNotFound {
+                        provider: PROVIDER.to_string(),
+                        version: game_version,
+                    })
                 }
             }
         }
@@ -119,14 +152,38 @@ impl PaperMC {
 }
 
 async fn gen_link(
+    base: &str,
     core_name: String,
     game_version: &String,
     last_build: &str,
-) -> Result<(String, Url), Box<dyn std::error::Error + Send + Sync + 'static>> {
+) -> Result<(String, Url), ProviderError> {
     let buildlink = format!(
-        "https://api.papermc.io/v2/projects/{}/versions/{}/builds/{}",
-        core_name, game_version, last_build
+        "{}/projects/{}/versions/{}/builds/{}",
+        base, core_name, game_version, last_build
     );
-    let url = reqwest::get(&buildlink).await?.json::<Url>().await?;
+    let url = get_json::<Url>(PROVIDER, &buildlink).await?;
     Ok((buildlink, url))
 }
+
+#[async_trait::async_trait]
+impl Provider for PaperMC {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        PaperMC::get_link(item).await
+    }
+
+    async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> {
+        let core_name = project(item)?;
+        let (list, _) = mirrored(PROVIDER, BASE, |base| {
+            let link = format!("{}/projects/{}", base, core_name);
+            async move { get_json::<VersionList>(PROVIDER, &link).await }
+        })
+        .await?;
+        let mut versions = list.versions;
+        versions.reverse();
+        Ok(versions)
+    }
+}
@@ -2,7 +2,7 @@ use prisma_core::item::Item;
 use prisma_hash::HashType;
 use serde::{Deserialize, Serialize};
 
-use crate::DownloadMeta;
+use crate::{get_json, mirrored, resolve_mirrored, DownloadMeta, Provider, ProviderError};
 
 pub struct Purpur;
 
@@ -31,56 +31,79 @@ struct FileHash {
 /// https://api.purpurmc.org/v2/purpur/{Version}/{Build}/download
 const MAIN_LINK: &str = "https://api.purpurmc.org/v2/purpur";
 
+const PROVIDER: &str = "purpur";
+
 /// Find version in version list, if exist give out version or give error
-async fn find_version(
-    version: Option<&str>,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let version_list = reqwest::get(MAIN_LINK)
-        .await?
-        .json::<VersionList>()
-        .await?
-        .versions;
-    version
-        .and_then(|v| version_list.iter().find(|x| x == &v))
-        .or_else(|| version_list.last())
-        .map(|v| v.to_owned())
-        .ok_or_else(|| "No versions available".into())
+async fn find_version(base: &str, version: Option<&str>) -> Result<String, ProviderError> {
+    let version_list = get_json::<VersionList>(PROVIDER, base).await?.versions;
+    match version {
+        Some(version) => version_list
+            .into_iter()
+            .find(|x| x == version)
+            .ok_or_else(|| ProviderError::VersionNotFound {
+                provider: PROVIDER.to_string(),
+                version: version.to_string(),
+            }),
+        None => version_list
+            .last()
+            .cloned()
+            .ok_or_else(|| ProviderError::NoVersions {
+                provider: PROVIDER.to_string(),
+            }),
+    }
 }
 
 impl Purpur {
-    pub async fn get_link(
-        item: &Item,
-    ) -> Result<DownloadMeta, Box<dyn std::error::Error + Send + Sync + 'static>> {
-        let version = find_version(item.version.game_version.as_deref()).await?;
+    pub async fn get_link(item: &Item) -> Result<DownloadMeta, ProviderError> {
+        resolve_mirrored(PROVIDER, MAIN_LINK, |base| Self::get_link_at(base, item)).await
+    }
+
+    /// Resolves against `base`, [MAIN_LINK] or a mirror of it
+    async fn get_link_at(base: String, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let version = find_version(&base, item.version.game_version.as_deref()).await?;
         //Version string
-        let verlink = format!("{}/{}", MAIN_LINK, version);
-        let build_list = reqwest::get(verlink).await?.json::<BuildList>().await?;
+        let verlink = format!("{}/{}", base, version);
+        let build_list = get_json::<BuildList>(PROVIDER, &verlink).await?;
         let build_list_latest = build_list.builds.latest;
         let build_list = build_list.builds.all;
 
         match item.version.version_build.as_ref() {
             Some(local_build) => {
                 if build_list.iter().any(|x| x == local_build) {
-                    let (build_link, file_hash) = gen_link(&version, local_build).await?;
+                    let (build_link, file_hash) = gen_link(&base, &version, local_build).await?;
 
                     Ok(DownloadMeta {
                         download_link: format!("{}/download", build_link),
                         hash: HashType::new_md5(file_hash.md5),
                         game_version: version,
                         version_build: Some(local_build.clone()),
+                        loader_version: None,
+                        installer_version: None,
+                        launch: None,
+                        build: None,
+                        base: None,
                     })
                 } else {
-                    Err(format!("not found version {} with build {}", version, local_build).into())
+                    Err(ProviderError::BuildNotFound {
+                        provider: PROVIDER.to_string(),
+                        version,
+                        build: local_build.clone(),
+                    })
                 }
             }
             None => {
-                let (build_link, file_hash) = gen_link(&version, &build_list_latest).await?;
+                let (build_link, file_hash) = gen_link(&base, &version, &build_list_latest).await?;
 
                 Ok(DownloadMeta {
                     download_link: format!("{}/download", build_link),
                     hash: HashType::new_md5(file_hash.md5),
                     game_version: version,
                     version_build: Some(build_list_latest),
+                    loader_version: None,
+                    installer_version: None,
+                    launch: None,
+                    build: None,
+                    base: None,
                 })
             }
         }
@@ -88,10 +111,32 @@ impl Purpur {
 }
 
 async fn gen_link(
+    base: &str,
     version: &str,
     local_build: &str,
-) -> Result<(String, FileHash), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let build_link = format!("{}/{}/{}", MAIN_LINK, version, &local_build);
-    let file_hash: FileHash = reqwest::get(&build_link).await?.json().await?;
+) -> Result<(String, FileHash), ProviderError> {
+    let build_link = format!("{}/{}/{}", base, version, &local_build);
+    let file_hash: FileHash = get_json(PROVIDER, &build_link).await?;
     Ok((build_link, file_hash))
 }
+
+#[async_trait::async_trait]
+impl Provider for Purpur {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        Purpur::get_link(item).await
+    }
+
+    async fn versions(&self, _item: &Item) -> Result<Vec<String>, ProviderError> {
+        let (list, _) = mirrored(PROVIDER, MAIN_LINK, |base| async move {
+            get_json::<VersionList>(PROVIDER, &base).await
+        })
+        .await?;
+        let mut versions = list.versions;
+        versions.reverse();
+        Ok(versions)
+    }
+}
@@ -0,0 +1,173 @@
+use prisma_core::{item::Item, version::Version};
+use prisma_hash::HashType;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    get_json, get_text,
+    providers::{
+        fabric::{find_game_version, list_game_versions},
+        vanilla::Vanilla,
+    },
+    rebase, resolve_mirrored, DownloadMeta, Launch, Library, Provider, ProviderError,
+};
+
+const PROVIDER: &str = "quilt";
+const META: &str = "https://meta.quiltmc.org/v3";
+
+/// Quilt server from meta.quiltmc.org
+///
+/// Quilt has no prebuilt server launcher, so the core is the vanilla server jar
+/// started through the loader main class with the libraries from the launch profile.
+pub struct Quilt;
+
+/// Loader compatible with a game version, from `/versions/loader/<game>`
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LoaderEntry {
+    loader: Loader,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Loader {
+    version: String,
+}
+
+/// Server launch profile from `/versions/loader/<game>/<loader>/server/json`
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Profile {
+    main_class: String,
+    libraries: Vec<ProfileLibrary>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ProfileLibrary {
+    /// Maven coordinates
+    name: String,
+    /// Maven repository
+    url: String,
+}
+
+impl Quilt {
+    /// Resolves game and loader versions, the vanilla server and the loader libraries
+    ///
+    /// Unpinned versions resolve to the latest stable one, or to the latest one
+    /// for the `Beta` channel.
+    pub async fn get_link(item: &Item) -> Result<DownloadMeta, ProviderError> {
+        resolve_mirrored(PROVIDER, META, |base| Self::get_link_at(base, item)).await
+    }
+
+    /// Resolves against `base`, [META] or a mirror of it
+    async fn get_link_at(base: String, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let version = &item.version;
+        let unstable = version.channel.is_beta();
+
+        // the meta is a fork of the Fabric one
+        let game_version =
+            find_game_version(PROVIDER, &base, version.game_version.as_deref(), unstable).await?;
+
+        let loaders: Vec<LoaderEntry> = get_json(
+            PROVIDER,
+            &format!("{}/versions/loader/{}", base, game_version),
+        )
+        .await?;
+        // newest first, pre-releases look like `0.26.0-beta.1`
+        let loader_version = loaders
+            .into_iter()
+            .map(|x| x.loader.version)
+            .find(|x| match &version.loader_version {
+                Some(pinned) => x == pinned,
+                None => unstable || !x.contains('-'),
+            })
+            .ok_or_else(|| ProviderError::LoaderNotFound {
+                provider: PROVIDER.to_string(),
+                component: "loader".to_string(),
+                version: version
+                    .loader_version
+                    .clone()
+                    .unwrap_or_else(|| "latest".to_string()),
+                game_version: game_version.clone(),
+            })?;
+
+        let profile: Profile = get_json(
+            PROVIDER,
+            &format!(
+                "{}/versions/loader/{}/{}/server/json",
+                base, game_version, loader_version
+            ),
+        )
+        .await?;
+
+        let mut libraries = Vec::with_capacity(profile.libraries.len());
+        for library in profile.libraries {
+            libraries.push(resolve_library(library, &base).await?);
+        }
+
+        let server = Vanilla::get_link(&item.clone().with_version(Version {
+            game_version: Some(game_version.clone()),
+            ..Default::default()
+        }))
+        .await?;
+
+        Ok(DownloadMeta {
+            download_link: server.download_link,
+            hash: server.hash,
+            game_version,
+            version_build: None,
+            loader_version: Some(loader_version),
+            installer_version: None,
+            launch: Some(Launch {
+                main_class: Some(profile.main_class),
+                jvm_args: vec![format!("-Dloader.gameJarPath={}", Launch::CORE_JAR)],
+                libraries,
+                install_args: vec![],
+            }),
+            build: None,
+            base: None,
+        })
+    }
+}
+
+/// Builds the download link of a library and takes its sha1 from the repository
+///
+/// The repository is moved onto `base` when the meta is a mirror.
+async fn resolve_library(library: ProfileLibrary, base: &str) -> Result<Library, ProviderError> {
+    let path = Library::path(&library.name).ok_or_else(|| ProviderError::NoDownload {
+        provider: PROVIDER.to_string(),
+        name: library.name.clone(),
+    })?;
+    let repository = rebase(&library.url, base, META);
+    let url = format!("{}/{}", repository.trim_end_matches('/'), path);
+
+    let hash = match get_text(PROVIDER, &format!("{}.sha1", url)).await {
+        Ok(sha1) => HashType::new_sha1(
+            sha1.split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        ),
+        // not every repository publishes checksums
+        Err(ProviderError::Status { status: 404, .. }) => HashType::None,
+        Err(e) => return Err(e),
+    };
+
+    Ok(Library {
+        name: library.name,
+        url,
+        hash,
+    })
+}
+
+#[async_trait::async_trait]
+impl Provider for Quilt {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        Quilt::get_link(item).await
+    }
+
+    async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> {
+        list_game_versions(PROVIDER, META, item.version.channel.is_beta()).await
+    }
+}
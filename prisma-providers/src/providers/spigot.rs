@@ -0,0 +1,174 @@
+use prisma_core::{item::Item, platform::Platform, provider::Provider as Source};
+use prisma_hash::HashType;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    get_json, get_text, mirrored, resolve_mirrored, Build, DownloadMeta, Provider, ProviderError,
+};
+
+const PROVIDER: &str = "spigot";
+const BASE: &str = "https://hub.spigotmc.org";
+/// Paths under [BASE]
+const BUILD_TOOLS: &str =
+    "/jenkins/job/BuildTools/lastSuccessfulBuild/artifact/target/BuildTools.jar";
+const VERSIONS: &str = "/versions";
+const BUILD_DATA: &str = "/stash/projects/SPIGOT/repos/builddata/raw/info.json";
+
+/// Spigot and CraftBukkit compiled by BuildTools
+///
+/// There are no prebuilt jars, so the link is BuildTools itself and the store runs it.
+/// BuildTools needs `git` and a java version supported by the revision.
+pub struct Spigot;
+
+/// Revision info from `/versions/<rev>.json`
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BuildInfo {
+    /// Build number
+    name: String,
+    refs: Refs,
+}
+
+/// Commits of the repositories BuildTools checks out
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Refs {
+    build_data: String,
+}
+
+/// Build data of a commit, from [BUILD_DATA]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildData {
+    /// Game version BuildTools compiles
+    minecraft_version: String,
+}
+
+impl Spigot {
+    /// Resolves the revision to a build number and the BuildTools arguments
+    ///
+    /// `game_version` is the revision, `latest` if not set, a pinned `version_build`
+    /// is passed to BuildTools as the revision instead. Without a pinned `game_version`
+    /// the game version is the one BuildTools builds for the revision.
+    pub async fn get_link(item: &Item) -> Result<DownloadMeta, ProviderError> {
+        resolve_mirrored(PROVIDER, BASE, |base| Self::get_link_at(base, item)).await
+    }
+
+    /// Resolves against `base`, [BASE] or a mirror of it
+    async fn get_link_at(base: String, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let platform = match &item.provider {
+            Source::Core(platform @ (Platform::Spigot | Platform::Bukkit)) => platform,
+            Source::Core(platform) | Source::Extension((_, platform, _)) => {
+                return Err(ProviderError::Unsupported {
+                    platform: platform.clone(),
+                })
+            }
+        };
+        let pinned = item.version.game_version.as_deref();
+        let rev = item
+            .version
+            .version_build
+            .as_deref()
+            .or(pinned)
+            .unwrap_or("latest");
+        let requested = pinned.unwrap_or(rev).to_string();
+
+        let info: BuildInfo =
+            match get_json(PROVIDER, &format!("{}{}/{}.json", base, VERSIONS, rev)).await {
+                Err(ProviderError::Status { status: 404, .. }) => {
+                    return Err(match &item.version.version_build {
+                        Some(build) => ProviderError::BuildNotFound {
+                            provider: PROVIDER.to_string(),
+                            version: requested,
+                            build: build.clone(),
+                        },
+                        None => ProviderError::VersionNotFound {
+                            provider: PROVIDER.to_string(),
+                            version: requested,
+                        },
+                    })
+                }
+                result => result?,
+            };
+        let game_version = match pinned {
+            Some(pinned) => pinned.to_string(),
+            None => {
+                let data: BuildData = get_json(
+                    PROVIDER,
+                    &format!("{}{}?at={}", base, BUILD_DATA, info.refs.build_data),
+                )
+                .await?;
+                data.minecraft_version
+            }
+        };
+
+        let mut args = vec![
+            "--rev".to_string(),
+            // `latest` would move on to newer revisions in later builds
+            item.version
+                .version_build
+                .clone()
+                .unwrap_or_else(|| game_version.clone()),
+            "--output-dir".to_string(),
+            Build::OUTPUT_DIR.to_string(),
+        ];
+        if platform.is_bukkit() {
+            args.extend(["--compile".to_string(), "craftbukkit".to_string()]);
+        }
+
+        Ok(DownloadMeta {
+            download_link: format!("{}{}", base, BUILD_TOOLS),
+            // jenkins doesn't publish hashes, the built jar is hashed by the store
+            hash: HashType::None,
+            version_build: Some(info.name.clone()),
+            loader_version: None,
+            installer_version: None,
+            launch: None,
+            build: Some(Build {
+                name: format!(
+                    "{}-{}-{}",
+                    platform.as_ref().to_lowercase(),
+                    game_version,
+                    info.name
+                ),
+                args,
+            }),
+            game_version,
+            base: None,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for Spigot {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        Spigot::get_link(item).await
+    }
+
+    /// Release revisions from the directory listing, newest first
+    async fn versions(&self, _item: &Item) -> Result<Vec<String>, ProviderError> {
+        let (listing, _) = mirrored(PROVIDER, BASE, |base| async move {
+            get_text(PROVIDER, &format!("{}{}/", base, VERSIONS)).await
+        })
+        .await?;
+        let mut versions = listing
+            .split("href=\"")
+            .skip(1)
+            .filter_map(|x| x.split_once(".json\""))
+            .map(|x| x.0)
+            .filter(|x| x.starts_with("1.") && x.chars().all(|c| c.is_ascii_digit() || c == '.'))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        versions.sort_by_key(|x| {
+            x.split('.')
+                .map(|x| x.parse::<u64>().unwrap_or_default())
+                .collect::<Vec<_>>()
+        });
+        versions.reverse();
+        versions.dedup();
+        Ok(versions)
+    }
+}
@@ -2,7 +2,13 @@ use prisma_core::item::Item;
 use prisma_hash::HashType;
 use serde::{Deserialize, Serialize};
 
-use crate::DownloadMeta;
+use crate::{get_json, mirrored, rebase, resolve_mirrored, DownloadMeta, Provider, ProviderError};
+
+const PROVIDER: &str = "vanilla";
+const BASE: &str = "https://launchermeta.mojang.com";
+/// Path of the version manifest under [BASE], versions in it have absolute links
+/// to other Mojang hosts, which a mirror serves under its own base
+const MANIFEST: &str = "/mc/game/version_manifest.json";
 
 /// Structure representing Minecraft version manifest
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -67,33 +73,39 @@ impl Vanilla {
     ///
     /// Makes request to Mojang API to find the download link for minecraft.jar
     /// Returns DownloadMeta containing URL, hash and version info
-    pub async fn get_link(
-        item: &Item,
-    ) -> Result<DownloadMeta, Box<dyn std::error::Error + Send + Sync + 'static>> {
-        let link = find_version(item.version.game_version.as_deref()).await?;
-        let response = reqwest::get(link.0).await?;
-        let download_section: DownloadSection = response.json().await?;
+    pub async fn get_link(item: &Item) -> Result<DownloadMeta, ProviderError> {
+        resolve_mirrored(PROVIDER, BASE, |base| Self::get_link_at(base, item)).await
+    }
+
+    /// Resolves with the manifest of `base`, [BASE] or a mirror of it
+    async fn get_link_at(base: String, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        let link = find_version(&base, item.version.game_version.as_deref()).await?;
+        let download_section: DownloadSection =
+            get_json(PROVIDER, &rebase(&link.1, &base, BASE)).await?;
 
         Ok(DownloadMeta {
-            download_link: download_section.downloads.server.url,
+            download_link: rebase(&download_section.downloads.server.url, &base, BASE),
             hash: HashType::new_sha1(download_section.downloads.server.sha1),
-            game_version: link.1,
+            game_version: link.0,
             version_build: None,
+            loader_version: None,
+            installer_version: None,
+            launch: None,
+            build: None,
+            base: None,
         })
     }
 }
 
 /// Finds version-specific download information
 ///
-/// Makes request to version manifest and returns tuple of (download URL, version string)
+/// Makes request to version manifest and returns tuple of (version string, version URL)
 /// If no version specified, returns latest release version
 async fn find_version(
+    base: &str,
     version: Option<&str>,
-) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    const LINK: &str = "https://launchermeta.mojang.com/mc/game/version_manifest.json";
-
-    let response = reqwest::get(LINK).await?;
-    let vanilla: Vanilla = response.json().await?;
+) -> Result<(String, String), ProviderError> {
+    let vanilla: Vanilla = get_json(PROVIDER, &format!("{}{}", base, MANIFEST)).await?;
     let local_version = match version {
         Some(e) => e.to_owned(),
         None => vanilla.latest.release,
@@ -104,5 +116,33 @@ async fn find_version(
         .iter()
         .find(|x| x.version.contains(&local_version))
         .map(|x| (x.version.clone(), x.url.clone()))
-        .ok_or_else(|| format!("Version {} not found", local_version).into())
+        .ok_or_else(|| ProviderError::VersionNotFound {
+            provider: PROVIDER.to_string(),
+            version: local_version,
+        })
+}
+
+#[async_trait::async_trait]
+impl Provider for Vanilla {
+    fn name(&self) -> &str {
+        PROVIDER
+    }
+
+    async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        Vanilla::get_link(item).await
+    }
+
+    /// Releases, snapshots too for the `Beta` channel
+    async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> {
+        let (vanilla, _) = mirrored(PROVIDER, BASE, |base| async move {
+            get_json::<Vanilla>(PROVIDER, &format!("{}{}", base, MANIFEST)).await
+        })
+        .await?;
+        Ok(vanilla
+            .versions
+            .into_iter()
+            .filter(|x| item.version.channel.is_beta() || x.type_field == TypeOfVersion::Release)
+            .map(|x| x.version)
+            .collect())
+    }
 }
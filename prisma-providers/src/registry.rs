@@ -0,0 +1,170 @@
+//! Providers by platform and extension provider name.
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use prisma_core::{extension::ExtensionType, item::Item, provider::Provider as Source};
+
+use crate::{
+    providers::{
+        fabric::Fabric, forge::Forge, modrinth::Modrinth, papermc::PaperMC, purpur::Purpur,
+        quilt::Quilt, spigot::Spigot, vanilla::Vanilla,
+    },
+    DownloadMeta, Provider, ProviderError,
+};
+
+static GLOBAL: RwLock<Option<Arc<Registry>>> = RwLock::new(None);
+
+/// Providers of cores by [Platform::key](prisma_core::platform::Platform::key) and of
+/// extensions by [ExtensionProvider::key](prisma_core::extension::ExtensionProvider::key)
+#[derive(Clone)]
+pub struct Registry {
+    cores: BTreeMap<String, Arc<dyn Provider>>,
+    extensions: BTreeMap<String, Arc<dyn Provider>>,
+}
+
+impl Registry {
+    /// Creates a registry without providers
+    pub fn empty() -> Self {
+        Self {
+            cores: BTreeMap::new(),
+            extensions: BTreeMap::new(),
+        }
+    }
+
+    /// Registry used by [DownloadMeta::fetch], the built-in providers by default
+    pub fn global() -> Arc<Registry> {
+        if let Some(registry) = GLOBAL.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            return registry.clone();
+        }
+        GLOBAL
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| Arc::new(Registry::default()))
+            .clone()
+    }
+
+    /// Replaces the global registry
+    pub fn set_global(registry: Registry) {
+        *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(registry));
+    }
+
+    /// Registers a core provider for the platform, replacing the previous one
+    pub fn with_core(mut self, platform: impl Into<String>, provider: Arc<dyn Provider>) -> Self {
+        self.cores.insert(platform.into().to_lowercase(), provider);
+        self
+    }
+
+    /// Registers an extension provider, replacing the previous one
+    pub fn with_extension(mut self, name: impl Into<String>, provider: Arc<dyn Provider>) -> Self {
+        self.extensions.insert(name.into().to_lowercase(), provider);
+        self
+    }
+
+    /// Registered core platforms
+    pub fn cores(&self) -> impl Iterator<Item = &str> {
+        self.cores.keys().map(String::as_str)
+    }
+
+    /// Registered extension providers
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.extensions.keys().map(String::as_str)
+    }
+
+    /// Provider of the item
+    pub fn get(&self, source: &Source) -> Result<&Arc<dyn Provider>, ProviderError> {
+        let (kind, providers, name) = match source {
+            Source::Core(platform) => ("core", &self.cores, platform.key()),
+            Source::Extension((_, _, ExtensionType::Mod(provider)))
+            | Source::Extension((_, _, ExtensionType::Plugin(provider))) => {
+                ("extension", &self.extensions, provider.key())
+            }
+        };
+        providers
+            .get(&name)
+            .ok_or_else(|| ProviderError::NotRegistered {
+                kind: kind.to_string(),
+                name,
+            })
+    }
+
+    /// Resolves the item with its provider
+    ///
+    /// Offline misses of the cache name the item, like `not cached: paper 1.20.1 build 17`.
+    pub async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+        match self.get(&item.provider)?.resolve(item).await {
+            Err(ProviderError::NotCached { .. }) => Err(ProviderError::NotCached {
+                what: describe(item),
+            }),
+            result => result,
+        }
+    }
+
+    /// Versions available for the item, newest first
+    pub async fn versions(&self, item: &Item) -> Result<Vec<String>, ProviderError> {
+        self.get(&item.provider)?.versions(item).await
+    }
+
+    /// Starts the download of a file resolved for the item
+    pub async fn download(
+        &self,
+        source: &Source,
+        url: &str,
+    ) -> Result<reqwest::Response, ProviderError> {
+        self.get(source)?.download(url).await
+    }
+}
+
+/// Item as requested, like `paper 1.20.1 build 17`
+fn describe(item: &Item) -> String {
+    let name = match &item.provider {
+        Source::Core(platform) => platform.key(),
+        Source::Extension((name, _, _)) => name.clone(),
+    };
+    let version = &item.version;
+    let mut description = format!(
+        "{} {}",
+        name,
+        version.game_version.as_deref().unwrap_or("latest")
+    );
+    if let Some(build) = &version.version_build {
+        description.push_str(&format!(" build {}", build));
+    }
+    if let Some(loader) = &version.loader_version {
+        description.push_str(&format!(" loader {}", loader));
+    }
+    description
+}
+
+impl Default for Registry {
+    /// Built-in providers
+    fn default() -> Self {
+        let paper: Arc<dyn Provider> = Arc::new(PaperMC);
+        let spigot: Arc<dyn Provider> = Arc::new(Spigot);
+        let forge: Arc<dyn Provider> = Arc::new(Forge);
+        Self::empty()
+            .with_core("vanilla", Arc::new(Vanilla::default()))
+            .with_core("spigot", spigot.clone())
+            .with_core("bukkit", spigot)
+            .with_core("paper", paper.clone())
+            .with_core("folia", paper.clone())
+            .with_core("waterfall", paper.clone())
+            .with_core("velocity", paper)
+            .with_core("purpur", Arc::new(Purpur))
+            .with_core("fabric", Arc::new(Fabric))
+            .with_core("quilt", Arc::new(Quilt))
+            .with_core("forge", forge.clone())
+            .with_core("neoforge", forge)
+            .with_extension("modrinth", Arc::new(Modrinth))
+    }
+}
+
+impl std::fmt::Debug for Registry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registry")
+            .field("cores", &self.cores.keys().collect::<Vec<_>>())
+            .field("extensions", &self.extensions.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
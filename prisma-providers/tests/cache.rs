@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod cache {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use prisma_config::network::NetworkOptions;
+    use prisma_providers::{Cache, Client};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Serves a body with an ETag, `304` if the request has it, counts the requests
+    async fn serve(requests: Arc<AtomicUsize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let read = stream.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).to_lowercase();
+                requests.fetch_add(1, Ordering::SeqCst);
+                let response = if request.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n"
+                } else {
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 4\r\nConnection: close\r\n\r\nbody"
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}/versions", address)
+    }
+
+    #[tokio::test]
+    async fn fresh_and_revalidated() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let url = serve(requests.clone()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = Client::new(&NetworkOptions::default())
+            .unwrap()
+            .with_cache(Some(Cache::new(dir.path())));
+
+        assert_eq!(
+            client.fetch("test", client.get(&url)).await.unwrap(),
+            "body"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        let entry = client.cache().unwrap().get(&url).await.unwrap();
+        assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+
+        // fresh, not asked again
+        assert_eq!(
+            client.fetch("test", client.get(&url)).await.unwrap(),
+            "body"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // refresh revalidates, the server answers 304
+        let client = client.with_cache(Some(Cache::new(dir.path()).with_refresh(true)));
+        assert_eq!(
+            client.fetch("test", client.get(&url)).await.unwrap(),
+            "body"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn disabled() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let url = serve(requests.clone()).await;
+        let client = Client::new(&NetworkOptions::default()).unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(
+                client.fetch("test", client.get(&url)).await.unwrap(),
+                "body"
+            );
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod client {
+    use prisma_config::network::NetworkOptions;
+    use prisma_providers::{Client, ProviderError};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Serves the responses in order, one per connection
+    async fn serve(responses: &'static [&'static str]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).await.unwrap();
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}/", address)
+    }
+
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const NOT_FOUND: &str =
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+
+    #[tokio::test]
+    async fn retries_server_errors() {
+        let url = serve(&[UNAVAILABLE, UNAVAILABLE, OK]).await;
+        let client = Client::new(&NetworkOptions::default()).unwrap();
+        let response = client.send("test", client.get(&url)).await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn gives_up() {
+        let url = serve(&[UNAVAILABLE, UNAVAILABLE]).await;
+        let client = Client::new(&NetworkOptions {
+            retries: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(matches!(
+            client.send("test", client.get(&url)).await,
+            Err(ProviderError::Status { status: 503, .. })
+        ));
+
+        // client errors aren't retried
+        let url = serve(&[NOT_FOUND]).await;
+        assert!(matches!(
+            client.send("test", client.get(&url)).await,
+            Err(ProviderError::Status { status: 404, .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_options() {
+        let options = NetworkOptions {
+            ca_certificates: vec!["./missing-ca.pem".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            Client::new(&options),
+            Err(ProviderError::Certificate { .. })
+        ));
+
+        let options = NetworkOptions {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            Client::new(&options),
+            Err(ProviderError::Client(_))
+        ));
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod limit {
+    use std::time::Duration;
+
+    use prisma_providers::Limiter;
+    use tokio::time::timeout;
+
+    const WAIT: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn global_and_per_host() {
+        let limiter = Limiter::new(2, 1);
+
+        let first = limiter.acquire("https://cdn.modrinth.com/a.jar").await;
+        // the host is busy
+        assert!(
+            timeout(WAIT, limiter.acquire("https://cdn.modrinth.com/b.jar"))
+                .await
+                .is_err()
+        );
+
+        // another host still has a slot, then every global slot is taken
+        let _second = limiter.acquire("https://api.papermc.io/v2").await;
+        assert!(
+            timeout(WAIT, limiter.acquire("https://maven.fabricmc.net/"))
+                .await
+                .is_err()
+        );
+
+        drop(first);
+        assert!(
+            timeout(WAIT, limiter.acquire("https://cdn.modrinth.com/b.jar"))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn unlimited() {
+        let limiter = Limiter::new(0, 0);
+        let mut permits = Vec::new();
+        for _ in 0..64 {
+            permits.push(limiter.acquire("https://cdn.modrinth.com/a.jar").await);
+        }
+    }
+}
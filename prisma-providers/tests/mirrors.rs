@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod mirrors {
+    use std::collections::BTreeMap;
+
+    use prisma_config::network::{Endpoint, NetworkOptions};
+    use prisma_core::{item::Item, platform::Platform, version::Version};
+    use prisma_providers::{Client, DownloadMeta};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::Mutex,
+    };
+
+    /// The tests set the global client, they take turns
+    static SERIAL: Mutex<()> = Mutex::const_new(());
+
+    /// Answers with the body of the first route the request path ends with
+    async fn fixture(routes: &'static [(&'static str, &'static str)]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let read = stream.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let response = match routes.iter().find(|(route, _)| path.ends_with(route)) {
+                    Some((_, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}/purpur", address)
+    }
+
+    /// Address nothing listens on
+    async fn unreachable() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        format!("http://{}/purpur", listener.local_addr().unwrap())
+    }
+
+    #[test]
+    fn endpoints() {
+        let client = Client::new(&NetworkOptions {
+            endpoints: BTreeMap::from([(
+                "papermc".to_string(),
+                Endpoint {
+                    url: None,
+                    mirrors: vec!["https://mirror.local/v2/".to_string()],
+                    ttl: None,
+                },
+            )]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            client.endpoints("papermc", "https://api.papermc.io/v2"),
+            ["https://api.papermc.io/v2", "https://mirror.local/v2"]
+        );
+        assert_eq!(
+            client.endpoints("purpur", "https://api.purpurmc.org/v2/purpur"),
+            ["https://api.purpurmc.org/v2/purpur"]
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_mirror() {
+        let _serial = SERIAL.lock().await;
+        let mirror = fixture(&[
+            ("/purpur/1.21.1/2329", r#"{"md5":"0123456789abcdef"}"#),
+            (
+                "/purpur/1.21.1",
+                r#"{"builds":{"latest":"2329","all":["2328","2329"]}}"#,
+            ),
+            ("/purpur", r#"{"versions":["1.21","1.21.1"]}"#),
+        ])
+        .await;
+        Client::set_global(
+            Client::new(&NetworkOptions {
+                retries: 0,
+                endpoints: BTreeMap::from([(
+                    "purpur".to_string(),
+                    Endpoint {
+                        url: Some(unreachable().await),
+                        mirrors: vec![mirror.clone()],
+                        ttl: None,
+                    },
+                )]),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+
+        let item = Item::new_core(Platform::Purpur).with_version(Version::default());
+        let meta = DownloadMeta::fetch(&item).await.unwrap();
+        assert_eq!(meta.game_version, "1.21.1");
+        assert_eq!(meta.version_build.as_deref(), Some("2329"));
+        assert_eq!(
+            meta.download_link,
+            format!("{}/1.21.1/2329/download", mirror)
+        );
+        assert_eq!(meta.base, Some(mirror));
+    }
+
+    #[tokio::test]
+    async fn rebases_upstream_links_onto_mirror() {
+        let _serial = SERIAL.lock().await;
+        let mirror = fixture(&[
+            (
+                "/mc/game/version_manifest.json",
+                r#"{"latest":{"release":"1.21.1","snapshot":"1.21.1"},"versions":[{"id":"1.21.1","type":"release","url":"https://piston-meta.mojang.com/v1/packages/0123/1.21.1.json"}]}"#,
+            ),
+            (
+                "/v1/packages/0123/1.21.1.json",
+                r#"{"downloads":{"server":{"sha1":"0123","url":"https://piston-data.mojang.com/v1/objects/0123/server.jar"}}}"#,
+            ),
+        ])
+        .await;
+        Client::set_global(
+            Client::new(&NetworkOptions {
+                retries: 0,
+                endpoints: BTreeMap::from([(
+                    "vanilla".to_string(),
+                    Endpoint {
+                        url: Some(unreachable().await),
+                        mirrors: vec![mirror.clone()],
+                        ttl: None,
+                    },
+                )]),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+
+        let item = Item::new_core(Platform::Vanilla).with_version(Version::default());
+        let meta = DownloadMeta::fetch(&item).await.unwrap();
+        assert_eq!(meta.game_version, "1.21.1");
+        assert_eq!(
+            meta.download_link,
+            format!("{}/v1/objects/0123/server.jar", mirror)
+        );
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod offline {
+    use prisma_config::network::NetworkOptions;
+    use prisma_core::{item::Item, platform::Platform, version::Version};
+    use prisma_providers::{cache::CacheEntry, Cache, Client, DownloadMeta, ProviderError};
+
+    #[tokio::test]
+    async fn resolves_from_cache_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let client = Client::new(&NetworkOptions {
+            offline: true,
+            ..Default::default()
+        })
+        .unwrap()
+        .with_cache(Some(cache.clone()));
+
+        // stale entries are used as they are
+        let url = "https://api.purpurmc.org/v2/purpur";
+        cache
+            .put(&CacheEntry {
+                url: url.to_string(),
+                etag: None,
+                last_modified: None,
+                fetched_at: 0,
+                body: r#"{"versions":["1.21.1"]}"#.to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            client.fetch("purpur", client.get(url)).await.unwrap(),
+            r#"{"versions":["1.21.1"]}"#
+        );
+
+        let missing = format!("{}/1.21.1", url);
+        assert!(matches!(
+            client.fetch("purpur", client.get(&missing)).await,
+            Err(ProviderError::NotCached { what }) if what == missing
+        ));
+        assert!(matches!(
+            client.send("purpur", client.get(url)).await,
+            Err(ProviderError::Offline { .. })
+        ));
+
+        Client::set_global(client);
+        let item = Item::new_core(Platform::Paper).with_version(Version {
+            game_version: Some("1.20.1".to_string()),
+            version_build: Some("17".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            DownloadMeta::fetch(&item).await.unwrap_err().to_string(),
+            "not cached: paper 1.20.1 build 17"
+        );
+    }
+}
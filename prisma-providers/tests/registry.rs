@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod registry {
+    use std::sync::Arc;
+
+    use prisma_core::{
+        extension::ExtensionProvider, item::Item, platform::Platform, version::Version,
+    };
+    use prisma_hash::HashType;
+    use prisma_providers::{DownloadMeta, Provider, ProviderError, Registry};
+
+    /// Provider of a fixed in-house jar
+    struct Inhouse;
+
+    #[prisma_providers::async_trait]
+    impl Provider for Inhouse {
+        fn name(&self) -> &str {
+            "inhouse"
+        }
+
+        async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+            Ok(DownloadMeta {
+                download_link: "https://repo.example.com/inhouse.jar".to_string(),
+                hash: HashType::None,
+                game_version: item
+                    .version
+                    .game_version
+                    .clone()
+                    .unwrap_or_else(|| "1.21".to_string()),
+                version_build: Some("7".to_string()),
+                loader_version: None,
+                installer_version: None,
+                launch: None,
+                build: None,
+                base: None,
+            })
+        }
+
+        async fn versions(&self, _item: &Item) -> Result<Vec<String>, ProviderError> {
+            Ok(vec!["1.21".to_string(), "1.20.4".to_string()])
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_core() {
+        let registry = Registry::empty().with_core("InHouse", Arc::new(Inhouse));
+        let item = Item::new_core(Platform::Custom("inhouse".to_string())).with_version(Version {
+            game_version: Some("1.20.4".to_string()),
+            ..Default::default()
+        });
+
+        let meta = registry.resolve(&item).await.unwrap();
+        assert_eq!(meta.game_version, "1.20.4");
+        assert_eq!(meta.version_build.as_deref(), Some("7"));
+        assert_eq!(registry.versions(&item).await.unwrap(), ["1.21", "1.20.4"]);
+        assert_eq!(registry.cores().collect::<Vec<_>>(), ["inhouse"]);
+    }
+
+    #[tokio::test]
+    async fn custom_extension() {
+        let registry = Registry::empty().with_extension("inhouse", Arc::new(Inhouse));
+        let item = Item::new_plugin(
+            "tools".to_string(),
+            Platform::Paper,
+            ExtensionProvider::Custom("inhouse".to_string()),
+        );
+        assert!(registry.resolve(&item).await.is_ok());
+
+        // cores and extensions are separate
+        let core = Item::new_core(Platform::Custom("inhouse".to_string()));
+        assert!(matches!(
+            registry.resolve(&core).await,
+            Err(ProviderError::NotRegistered { kind, name }) if kind == "core" && name == "inhouse"
+        ));
+    }
+
+    #[test]
+    fn builtin() {
+        let registry = Registry::default();
+        for platform in [
+            Platform::Vanilla,
+            Platform::Paper,
+            Platform::Purpur,
+            Platform::Spigot,
+            Platform::Fabric,
+            Platform::NeoForge,
+        ] {
+            assert!(registry.get(&Item::new_core(platform).provider).is_ok());
+        }
+        assert_eq!(registry.extensions().collect::<Vec<_>>(), ["modrinth"]);
+    }
+
+    #[test]
+    fn platform_names() {
+        assert_eq!(
+            "inhouse".parse::<Platform>().unwrap(),
+            Platform::Custom("inhouse".to_string())
+        );
+        assert_eq!(Platform::NeoForge.key(), "neoforge");
+        assert_eq!(Platform::Custom("InHouse".to_string()).key(), "inhouse");
+    }
+
+    #[tokio::test]
+    async fn forge_rejects_old_game_versions() {
+        let item = Item::new_core(Platform::Forge).with_version(Version {
+            game_version: Some("1.16.5".to_string()),
+            ..Default::default()
+        });
+        let error = Registry::default().resolve(&item).await.unwrap_err();
+        assert!(
+            matches!(&error, ProviderError::UnsupportedVersion { version, .. } if version == "1.16.5")
+        );
+        assert!(error.to_string().contains("1.16.5"));
+    }
+}
@@ -0,0 +1,36 @@
+use std::{path::PathBuf, process::ExitStatus};
+
+/// Errors of launching and supervising the server
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    /// Store has no core to launch
+    #[error("core isn't installed, run `prisma sync` first")]
+    CoreNotInstalled,
+    /// Server process couldn't be spawned or awaited
+    #[error("server process: {0}")]
+    Process(#[from] std::io::Error),
+    /// Server kept crashing after every restart
+    #[error("server crashed {restarts} times in a row, last exit: {status}")]
+    CrashLoop { restarts: u32, status: ExitStatus },
+    /// State file couldn't be read or written
+    #[error("{}: {source}", path.display())]
+    State {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Server file, like `server.properties`, couldn't be read or written
+    #[error("{}: {source}", path.display())]
+    File {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// State file isn't valid
+    #[error("{}:{source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: ron::error::SpannedError,
+    },
+    /// State couldn't be serialized
+    #[error(transparent)]
+    Serialize(#[from] ron::Error),
+}
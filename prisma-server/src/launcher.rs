@@ -0,0 +1,196 @@
+//! Launcher of the deployed server core.
+use std::{
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+};
+
+use prisma_config::config::CoreOptions;
+use prisma_core::USER_JVM_ARGS_PATH;
+use prisma_providers::Launch;
+use prisma_store::store::{Store, StoreLaunch};
+use tokio::process::{Child, Command};
+
+use crate::ServerError;
+
+/// Java command line of a server
+#[derive(Debug, Clone)]
+pub struct Launcher {
+    /// Java executable
+    pub java: String,
+    /// Core jar, relative to `dir`
+    pub jar: PathBuf,
+    /// Server directory
+    pub dir: PathBuf,
+    /// Memory and java arguments
+    pub options: CoreOptions,
+    /// Main class and classpath for loader cores, `-jar` is used without it
+    pub launch: Option<StoreLaunch>,
+}
+
+impl Launcher {
+    /// Creates a launcher for the jar in the current directory
+    pub fn new(jar: impl Into<PathBuf>, options: CoreOptions) -> Self {
+        Self {
+            java: "java".to_string(),
+            jar: jar.into(),
+            dir: PathBuf::from("."),
+            options,
+            launch: None,
+        }
+    }
+
+    /// Creates a launcher for the core deployed by the store
+    pub fn from_store(store: &Store, options: CoreOptions) -> Result<Self, ServerError> {
+        let core = store
+            .inner
+            .iter()
+            .find(|x| x.item.provider.is_core())
+            .ok_or(ServerError::CoreNotInstalled)?;
+        Ok(Self::new(&core.symbol_link, options).with_launch(core.launch.clone()))
+    }
+
+    /// Sets the main class and classpath
+    pub fn with_launch(mut self, launch: Option<StoreLaunch>) -> Self {
+        self.launch = launch;
+        self
+    }
+
+    /// Sets the java executable
+    pub fn with_java(mut self, java: impl Into<String>) -> Self {
+        self.java = java.into();
+        self
+    }
+
+    /// Sets the server directory
+    pub fn with_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// JVM arguments: memory, configured java args, jar or classpath and main class, `nogui`
+    pub fn args(&self) -> Vec<String> {
+        let jar = self.jar.to_string_lossy().to_string();
+        let mut args = vec![
+            format!("-Xms{}M", self.options.min_memory),
+            format!("-Xmx{}M", self.options.max_memory),
+        ];
+        args.extend(self.options.java_args.iter().cloned());
+        match &self.launch {
+            Some(launch) => {
+                args.extend(
+                    launch
+                        .jvm_args
+                        .iter()
+                        .map(|x| x.replace(Launch::CORE_JAR, &jar)),
+                );
+                // without a main class the args files start the server
+                if let Some(main_class) = &launch.main_class {
+                    let separator = if cfg!(windows) { ";" } else { ":" };
+                    args.push("-cp".to_string());
+                    args.push(
+                        launch
+                            .libraries
+                            .iter()
+                            .map(|x| x.symbol_link.as_str())
+                            .collect::<Vec<_>>()
+                            .join(separator),
+                    );
+                    args.push(main_class.clone());
+                }
+            }
+            None => {
+                args.push("-jar".to_string());
+                args.push(jar);
+            }
+        }
+        args.push("nogui".to_string());
+        args
+    }
+
+    /// Command ready to spawn in the server directory
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(&self.java);
+        command.args(self.args()).current_dir(&self.dir);
+        command
+    }
+
+    /// Spawns the server with piped stdin, stdout and stderr
+    pub fn spawn(&self) -> std::io::Result<Child> {
+        self.command()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+    }
+
+    /// Runs the server attached to the current console until it exits
+    ///
+    /// Output is streamed to our stdout/stderr and our stdin is forwarded to the server.
+    pub async fn run(&self) -> std::io::Result<ExitStatus> {
+        let mut child = self.spawn()?;
+        let (stdout, stderr) = attach_output(&mut child);
+        let stdin = attach_input(&mut child);
+
+        let status = child.wait().await;
+        stdin.abort();
+        let _ = stdout.await;
+        let _ = stderr.await;
+        status
+    }
+}
+
+/// Writes memory and java arguments to [USER_JVM_ARGS_PATH], read by `run.sh` of Forge
+pub async fn write_user_jvm_args(options: &CoreOptions) -> Result<(), ServerError> {
+    let mut text = String::from("# Managed by prisma, edit java_args in the config instead\n");
+    text.push_str(&format!(
+        "-Xms{}M\n-Xmx{}M\n",
+        options.min_memory, options.max_memory
+    ));
+    for arg in &options.java_args {
+        text.push_str(arg);
+        text.push('\n');
+    }
+    tokio::fs::write(USER_JVM_ARGS_PATH, text)
+        .await
+        .map_err(|source| ServerError::File {
+            path: USER_JVM_ARGS_PATH.into(),
+            source,
+        })
+}
+
+/// Copies the server stdout and stderr to ours
+pub(crate) fn attach_output(
+    child: &mut Child,
+) -> (
+    tokio::task::JoinHandle<std::io::Result<u64>>,
+    tokio::task::JoinHandle<std::io::Result<u64>>,
+) {
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    (
+        tokio::spawn(async move {
+            match stdout.as_mut() {
+                Some(out) => tokio::io::copy(out, &mut tokio::io::stdout()).await,
+                None => Ok(0),
+            }
+        }),
+        tokio::spawn(async move {
+            match stderr.as_mut() {
+                Some(err) => tokio::io::copy(err, &mut tokio::io::stderr()).await,
+                None => Ok(0),
+            }
+        }),
+    )
+}
+
+/// Forwards our stdin to the server
+pub(crate) fn attach_input(child: &mut Child) -> tokio::task::JoinHandle<std::io::Result<u64>> {
+    let mut stdin = child.stdin.take();
+    tokio::spawn(async move {
+        match stdin.as_mut() {
+            Some(input) => tokio::io::copy(&mut tokio::io::stdin(), input).await,
+            None => Ok(0),
+        }
+    })
+}
@@ -0,0 +1,5 @@
+pub mod error;
+pub mod launcher;
+pub mod properties;
+pub mod supervisor;
+pub use error::ServerError;
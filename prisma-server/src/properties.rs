@@ -0,0 +1,346 @@
+//! `server.properties` rendered from the config.
+//!
+//! Only keys declared in the config are written, unknown keys, comments and order stay
+//! as they are. Values written by the last sync are kept in [PROPERTIES_STATE_PATH],
+//! a managed key that differs from them was edited by hand.
+use std::{collections::BTreeMap, path::Path};
+
+use prisma_config::config::CoreOptions;
+use prisma_core::{PROPERTIES_STATE_PATH, SERVER_PROPERTIES_PATH};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::ServerError;
+
+/// Parsed `server.properties` that renders back byte for byte
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertiesFile {
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Line {
+    /// `key=value`, `raw` is the original text with continuation lines
+    Entry {
+        key: String,
+        value: String,
+        raw: String,
+    },
+    /// Comment or blank line
+    Other(String),
+}
+
+/// Managed value that was changed outside of prisma
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub key: String,
+    /// Value written by the last sync
+    pub expected: String,
+    /// Value found in the file
+    pub found: String,
+}
+
+/// Managed value that was written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub key: String,
+    /// Previous value, `None` if the key was added
+    pub old: Option<String>,
+    pub new: String,
+}
+
+/// Outcome of a sync
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertiesReport {
+    pub changed: Vec<Change>,
+    /// Hand edits of managed keys, overwritten by the config
+    pub drift: Vec<Drift>,
+}
+
+/// Content of [PROPERTIES_STATE_PATH]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertiesState {
+    /// Managed keys and the values written by the last sync
+    pub written: BTreeMap<String, String>,
+}
+
+impl PropertiesFile {
+    /// Parses the java properties format
+    pub fn parse(text: &str) -> Self {
+        let mut lines = Vec::new();
+        let mut source = text.split_inclusive('\n');
+        while let Some(line) = source.next() {
+            let trimmed = line.trim_start();
+            if trimmed.trim_end().is_empty() || trimmed.starts_with(['#', '!']) {
+                lines.push(Line::Other(line.to_string()));
+                continue;
+            }
+
+            // join continuation lines, leading whitespace of the next line is dropped
+            let mut raw = line.to_string();
+            let mut logical = line.trim_end_matches(['\r', '\n']).to_string();
+            while continues(&logical) {
+                logical.pop();
+                match source.next() {
+                    Some(next) => {
+                        raw.push_str(next);
+                        logical.push_str(next.trim_end_matches(['\r', '\n']).trim_start());
+                    }
+                    None => break,
+                }
+            }
+
+            let (key, value) = split_entry(logical.trim_start());
+            lines.push(Line::Entry {
+                key: unescape(key),
+                value: unescape(value),
+                raw,
+            });
+        }
+        Self { lines }
+    }
+
+    /// Value of the last entry with this key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|line| match line {
+            Line::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Sets a value in place, or appends the key to the end
+    pub fn set(&mut self, key: &str, value: &str) {
+        let raw = format!("{}={}\n", escape(key, true), escape(value, false));
+        let existing = self.lines.iter_mut().rev().find_map(|line| match line {
+            Line::Entry { key: k, value, raw } if k == key => Some((value, raw)),
+            _ => None,
+        });
+        match existing {
+            Some((old, old_raw)) => {
+                *old = value.to_string();
+                *old_raw = raw;
+            }
+            None => {
+                if let Some(Line::Entry { raw, .. } | Line::Other(raw)) = self.lines.last_mut() {
+                    if !raw.ends_with('\n') {
+                        raw.push('\n');
+                    }
+                }
+                self.lines.push(Line::Entry {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    raw,
+                });
+            }
+        }
+    }
+
+    /// Renders the file, untouched lines are kept as they were
+    pub fn render(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| match line {
+                Line::Entry { raw, .. } | Line::Other(raw) => raw.as_str(),
+            })
+            .collect()
+    }
+
+    /// Writes the managed values
+    ///
+    /// `written` are the values of the last sync, used to detect hand edits.
+    pub fn sync(
+        &mut self,
+        managed: &[(String, String)],
+        written: &BTreeMap<String, String>,
+    ) -> PropertiesReport {
+        let mut report = PropertiesReport::default();
+        for (key, value) in managed {
+            let current = self.get(key).map(str::to_string);
+            if let (Some(expected), Some(found)) = (written.get(key), &current) {
+                if expected != found {
+                    report.drift.push(Drift {
+                        key: key.clone(),
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    });
+                }
+            }
+            if current.as_ref() != Some(value) {
+                self.set(key, value);
+                report.changed.push(Change {
+                    key: key.clone(),
+                    old: current,
+                    new: value.clone(),
+                });
+            }
+        }
+        report
+    }
+}
+
+impl PropertiesState {
+    /// Reads the state, missing file is an empty state
+    pub async fn load(path: &str) -> Result<Self, ServerError> {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => ron::de::from_bytes(&bytes).map_err(|source| ServerError::Parse {
+                path: path.into(),
+                source,
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(ServerError::State {
+                path: path.into(),
+                source,
+            }),
+        }
+    }
+
+    /// Writes the state
+    pub async fn save(&self, path: &str) -> Result<(), ServerError> {
+        tokio::fs::write(
+            path,
+            ron::ser::to_string_pretty(self, PrettyConfig::default())?,
+        )
+        .await
+        .map_err(|source| ServerError::State {
+            path: path.into(),
+            source,
+        })
+    }
+}
+
+/// Syncs [SERVER_PROPERTIES_PATH] with the config
+///
+/// The file is created if it doesn't exist and written only if something changed.
+pub async fn sync_properties(options: &CoreOptions) -> Result<PropertiesReport, ServerError> {
+    sync_properties_at(options, SERVER_PROPERTIES_PATH, PROPERTIES_STATE_PATH).await
+}
+
+/// Same as [sync_properties], with custom file locations
+pub async fn sync_properties_at(
+    options: &CoreOptions,
+    path: impl AsRef<Path>,
+    state_path: &str,
+) -> Result<PropertiesReport, ServerError> {
+    let path = path.as_ref();
+    let io_error = |source| ServerError::File {
+        path: path.into(),
+        source,
+    };
+    let text = match tokio::fs::read_to_string(path).await {
+        Ok(text) => Some(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(io_error(e)),
+    };
+
+    let mut file = PropertiesFile::parse(text.as_deref().unwrap_or_default());
+    let state = PropertiesState::load(state_path).await?;
+    let managed = options.server_properties();
+    let report = file.sync(&managed, &state.written);
+
+    if text.is_none() || !report.changed.is_empty() {
+        tokio::fs::write(path, file.render())
+            .await
+            .map_err(io_error)?;
+    }
+    let written = PropertiesState {
+        written: managed.into_iter().collect(),
+    };
+    if written != state {
+        written.save(state_path).await?;
+    }
+    Ok(report)
+}
+
+/// Line ends with an odd number of backslashes
+fn continues(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// Splits a logical line into escaped key and value
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    let end = line
+        .char_indices()
+        .find(|&(_, c)| {
+            let found = !escaped && matches!(c, '=' | ':' | ' ' | '\t' | '\x0c');
+            escaped = !escaped && c == '\\';
+            found
+        })
+        .map_or(line.len(), |(i, _)| i);
+    let (key, rest) = line.split_at(end);
+
+    let rest = rest.trim_start_matches([' ', '\t', '\x0c']);
+    let rest = rest.strip_prefix(['=', ':']).unwrap_or(rest);
+    (key, rest.trim_start_matches([' ', '\t', '\x0c']))
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    let mut units = Vec::new();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            flush_utf16(&mut units, &mut out);
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('u') => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+                match u16::from_str_radix(&hex, 16) {
+                    Ok(unit) => units.push(unit),
+                    Err(_) => {
+                        flush_utf16(&mut units, &mut out);
+                        out.push_str(&hex);
+                    }
+                }
+                continue;
+            }
+            Some(c) => {
+                flush_utf16(&mut units, &mut out);
+                out.push(match c {
+                    't' => '\t',
+                    'n' => '\n',
+                    'r' => '\r',
+                    'f' => '\x0c',
+                    c => c,
+                });
+            }
+            None => {}
+        }
+    }
+    flush_utf16(&mut units, &mut out);
+    out
+}
+
+/// Decodes pending `\uXXXX` units, surrogate pairs included
+fn flush_utf16(units: &mut Vec<u16>, out: &mut String) {
+    out.extend(char::decode_utf16(units.drain(..)).map(|x| x.unwrap_or('\u{fffd}')));
+}
+
+/// Escapes like `java.util.Properties::store`
+fn escape(text: &str, key: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\x0c' => out.push_str("\\f"),
+            ' ' if key || i == 0 => out.push_str("\\ "),
+            '=' | ':' | '#' | '!' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            c => {
+                let mut buf = [0; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    out.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+        }
+    }
+    out
+}
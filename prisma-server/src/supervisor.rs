@@ -0,0 +1,374 @@
+//! Supervisor that keeps the server running.
+//!
+//! - `SIGTERM`/`Ctrl+C` sends `stop` to the console and kills the server after a timeout
+//! - non-zero exit restarts the server with exponential backoff
+//! - too many crashes in a row stop the supervisor
+//! - current state is written to [SERVER_STATE_PATH]
+//! - restarts and kills are reported as [SupervisorEvent]
+use std::{
+    path::PathBuf,
+    process::ExitStatus,
+    time::{Duration, Instant},
+};
+
+use prisma_core::{time::now, SERVER_STATE_PATH};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin},
+    sync::mpsc,
+};
+
+use crate::{
+    launcher::{attach_output, Launcher},
+    ServerError,
+};
+
+/// Lifecycle of the supervised server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServerState {
+    /// Server process is running
+    Running,
+    /// `stop` was sent, waiting for exit
+    Stopping,
+    /// Waiting before the next restart
+    Backoff,
+    /// Stopped on request or exited cleanly
+    Stopped,
+    /// Gave up after too many crashes
+    Crashed,
+}
+
+/// Content of the state file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SupervisorState {
+    /// Pid of the supervisor
+    pub supervisor_pid: u32,
+    /// Pid of the server, if it is running
+    pub pid: Option<u32>,
+    pub state: ServerState,
+    /// Restarts since the last stable run
+    pub restarts: u32,
+    /// Exit code of the last run
+    pub last_exit: Option<i32>,
+    /// Unix time of the last update
+    pub updated_at: u64,
+}
+
+/// Restart or kill made by the supervisor, see [Supervisor::with_events]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupervisorEvent {
+    /// Server exited with `status` and is started again after `delay`
+    Restarting {
+        status: ExitStatus,
+        delay: Duration,
+        restarts: u32,
+        max_restarts: u32,
+    },
+    /// Server didn't stop within `timeout` and was killed
+    Killed { timeout: Duration },
+}
+
+/// Restarts the server until it is stopped
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    launcher: Launcher,
+    /// How long to wait for the server after `stop`
+    stop_timeout: Duration,
+    /// First restart delay, doubled after every crash
+    backoff: Duration,
+    /// Upper bound of the restart delay
+    max_backoff: Duration,
+    /// Crashes in a row before giving up
+    max_restarts: u32,
+    /// Run that lasted this long resets the crash counter
+    stable_after: Duration,
+    /// Where to write [SupervisorState]
+    state_path: PathBuf,
+    /// Receiver of the [SupervisorEvent], if any
+    events: Option<mpsc::UnboundedSender<SupervisorEvent>>,
+}
+
+impl Supervisor {
+    pub fn new(launcher: Launcher) -> Self {
+        Self {
+            launcher,
+            stop_timeout: Duration::from_secs(60),
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(120),
+            max_restarts: 5,
+            stable_after: Duration::from_secs(300),
+            state_path: PathBuf::from(SERVER_STATE_PATH),
+            events: None,
+        }
+    }
+
+    pub fn with_stop_timeout(mut self, stop_timeout: Duration) -> Self {
+        self.stop_timeout = stop_timeout;
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: Duration, max_backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    pub fn with_stable_after(mut self, stable_after: Duration) -> Self {
+        self.stable_after = stable_after;
+        self
+    }
+
+    pub fn with_state_path(mut self, state_path: impl Into<PathBuf>) -> Self {
+        self.state_path = state_path.into();
+        self
+    }
+
+    /// Sends restarts and kills to `events`
+    pub fn with_events(mut self, events: mpsc::UnboundedSender<SupervisorEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Runs the server until it is stopped by a signal, exits cleanly or crash-loops
+    ///
+    /// Returns the last exit status of the server.
+    pub async fn run(&self) -> Result<ExitStatus, ServerError> {
+        let mut console = forward_console();
+        let mut shutdown = Shutdown::register();
+        let mut restarts = 0;
+        let mut backoff = self.backoff;
+
+        loop {
+            let started = Instant::now();
+            let mut child = self.launcher.spawn()?;
+            let (stdout, stderr) = attach_output(&mut child);
+            let mut stdin = child.stdin.take();
+            self.write_state(child.id(), ServerState::Running, restarts, None)
+                .await?;
+
+            let exited = tokio::select! {
+                status = pump_console(&mut child, stdin.as_mut(), &mut console) => Some(status?),
+                _ = shutdown.recv() => None,
+            };
+            let (status, stopped) = match exited {
+                Some(status) => (status, false),
+                None => (self.stop(&mut child, stdin.as_mut(), restarts).await?, true),
+            };
+            let _ = stdout.await;
+            let _ = stderr.await;
+
+            if stopped || status.success() {
+                self.write_state(None, ServerState::Stopped, restarts, status.code())
+                    .await?;
+                return Ok(status);
+            }
+
+            if started.elapsed() >= self.stable_after {
+                restarts = 0;
+                backoff = self.backoff;
+            }
+            restarts += 1;
+            if restarts > self.max_restarts {
+                self.write_state(None, ServerState::Crashed, restarts, status.code())
+                    .await?;
+                return Err(ServerError::CrashLoop { restarts, status });
+            }
+
+            self.write_state(None, ServerState::Backoff, restarts, status.code())
+                .await?;
+            self.send(SupervisorEvent::Restarting {
+                status,
+                delay: backoff,
+                restarts,
+                max_restarts: self.max_restarts,
+            });
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = shutdown.recv() => {
+                    self.write_state(None, ServerState::Stopped, restarts, status.code())
+                        .await?;
+                    return Ok(status);
+                }
+            }
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+
+    /// Sends `stop` and waits for exit, kills the server after the timeout
+    async fn stop(
+        &self,
+        child: &mut Child,
+        stdin: Option<&mut ChildStdin>,
+        restarts: u32,
+    ) -> Result<ExitStatus, ServerError> {
+        self.write_state(child.id(), ServerState::Stopping, restarts, None)
+            .await?;
+        if let Some(stdin) = stdin {
+            // server may already be gone
+            let _ = stdin.write_all(b"stop\n").await;
+            let _ = stdin.flush().await;
+        }
+        match tokio::time::timeout(self.stop_timeout, child.wait()).await {
+            Ok(status) => Ok(status?),
+            Err(_) => {
+                self.send(SupervisorEvent::Killed {
+                    timeout: self.stop_timeout,
+                });
+                child.kill().await?;
+                Ok(child.wait().await?)
+            }
+        }
+    }
+
+    /// Reports an event, the receiver may be gone
+    fn send(&self, event: SupervisorEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
+    }
+
+    /// Writes the state file
+    async fn write_state(
+        &self,
+        pid: Option<u32>,
+        state: ServerState,
+        restarts: u32,
+        last_exit: Option<i32>,
+    ) -> Result<(), ServerError> {
+        let state = SupervisorState {
+            supervisor_pid: std::process::id(),
+            pid,
+            state,
+            restarts,
+            last_exit,
+            updated_at: now(),
+        };
+        tokio::fs::write(
+            &self.state_path,
+            ron::ser::to_string_pretty(&state, PrettyConfig::default())?,
+        )
+        .await
+        .map_err(|source| ServerError::State {
+            path: self.state_path.clone(),
+            source,
+        })?;
+        Ok(())
+    }
+}
+
+impl SupervisorState {
+    /// Reads the state file
+    pub async fn load(path: &str) -> Result<Self, ServerError> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|source| ServerError::State {
+                path: path.into(),
+                source,
+            })?;
+        ron::de::from_bytes(&bytes).map_err(|source| ServerError::Parse {
+            path: path.into(),
+            source,
+        })
+    }
+}
+
+/// Reads our stdin line by line, survives server restarts
+fn forward_console() -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tx.send(line).await.is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Forwards console lines to the server until it exits
+async fn pump_console(
+    child: &mut Child,
+    mut stdin: Option<&mut ChildStdin>,
+    console: &mut mpsc::Receiver<String>,
+) -> std::io::Result<ExitStatus> {
+    loop {
+        tokio::select! {
+            status = child.wait() => return status,
+            Some(line) = console.recv(), if stdin.is_some() => {
+                if let Some(input) = stdin.as_mut() {
+                    input.write_all(format!("{}\n", line).as_bytes()).await?;
+                    input.flush().await?;
+                }
+            }
+        }
+    }
+}
+
+/// `SIGTERM` and `Ctrl+C` listeners, registered once so that a signal received between
+/// two waits isn't lost
+struct Shutdown {
+    #[cfg(unix)]
+    terminate: Option<tokio::signal::unix::Signal>,
+    #[cfg(unix)]
+    interrupt: Option<tokio::signal::unix::Signal>,
+    #[cfg(windows)]
+    ctrl_c: Option<tokio::signal::windows::CtrlC>,
+}
+
+impl Shutdown {
+    fn register() -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            Self {
+                terminate: signal(SignalKind::terminate()).ok(),
+                interrupt: signal(SignalKind::interrupt()).ok(),
+            }
+        }
+        #[cfg(windows)]
+        {
+            Self {
+                ctrl_c: tokio::signal::windows::ctrl_c().ok(),
+            }
+        }
+    }
+
+    /// Resolves on `SIGTERM` or `Ctrl+C`, including one received before the call
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        {
+            tokio::select! {
+                _ = wait(self.terminate.as_mut()) => {}
+                _ = wait(self.interrupt.as_mut()) => {}
+            }
+        }
+        #[cfg(windows)]
+        {
+            match self.ctrl_c.as_mut() {
+                Some(ctrl_c) => {
+                    ctrl_c.recv().await;
+                }
+                None => std::future::pending().await,
+            }
+        }
+    }
+}
+
+/// Next delivery of a signal, never if it couldn't be registered
+#[cfg(unix)]
+async fn wait(signal: Option<&mut tokio::signal::unix::Signal>) {
+    match signal {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod launcher {
+    use prisma_config::config::CoreOptions;
+    use prisma_hash::HashType;
+    use prisma_server::launcher::Launcher;
+    use prisma_store::store::{StoreLaunch, StoreLibrary};
+
+    fn library(name: &str) -> StoreLibrary {
+        StoreLibrary {
+            name: name.to_string(),
+            hash: HashType::None,
+            path: format!("./.prisma/libraries/{}.jar", name),
+            symbol_link: format!("./libraries/{}.jar", name),
+            url: String::new(),
+        }
+    }
+
+    #[test]
+    fn args() {
+        let options = CoreOptions {
+            java_args: vec![],
+            ..Default::default()
+        };
+        let launcher = Launcher::new("./server.jar", options);
+        assert_eq!(
+            launcher.args(),
+            ["-Xms1024M", "-Xmx2048M", "-jar", "./server.jar", "nogui"]
+        );
+
+        let launcher = launcher.with_launch(Some(StoreLaunch {
+            main_class: Some("org.quiltmc.loader.impl.launch.knot.KnotServer".to_string()),
+            jvm_args: vec!["-Dloader.gameJarPath=${core_jar}".to_string()],
+            libraries: vec![library("loader"), library("asm")],
+            install_args: vec![],
+        }));
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        assert_eq!(
+            launcher.args(),
+            [
+                "-Xms1024M".to_string(),
+                "-Xmx2048M".to_string(),
+                "-Dloader.gameJarPath=./server.jar".to_string(),
+                "-cp".to_string(),
+                format!("./libraries/loader.jar{}./libraries/asm.jar", separator),
+                "org.quiltmc.loader.impl.launch.knot.KnotServer".to_string(),
+                "nogui".to_string(),
+            ]
+        );
+
+        let launcher = launcher.with_launch(Some(StoreLaunch {
+            main_class: None,
+            jvm_args: vec!["@libraries/net/neoforged/neoforge/21.1.77/unix_args.txt".to_string()],
+            libraries: vec![library("neoforge")],
+            install_args: vec!["--installServer".to_string()],
+        }));
+        assert_eq!(
+            launcher.args(),
+            [
+                "-Xms1024M",
+                "-Xmx2048M",
+                "@libraries/net/neoforged/neoforge/21.1.77/unix_args.txt",
+                "nogui",
+            ]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod properties {
+    use std::collections::BTreeMap;
+
+    use prisma_server::properties::{Drift, PropertiesFile};
+
+    const FILE: &str = "#Minecraft server properties\n\
+        #Sat Oct 18 12:00:00 UTC 2026\n\
+        motd=A Minecraft Server\n\
+        some-mod-key=keep me\n\
+        max-players=20\n\
+        pvp=true\n";
+
+    fn managed(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn render_unchanged() {
+        let file = PropertiesFile::parse(FILE);
+        assert_eq!(file.render(), FILE);
+        assert_eq!(file.get("some-mod-key"), Some("keep me"));
+    }
+
+    #[test]
+    fn sync_declared_keys_only() {
+        let mut file = PropertiesFile::parse(FILE);
+        let report = file.sync(
+            &managed(&[("max-players", "50"), ("view-distance", "8")]),
+            &BTreeMap::new(),
+        );
+        assert_eq!(report.changed.len(), 2);
+        assert!(report.drift.is_empty());
+        assert_eq!(
+            file.render(),
+            FILE.replace("max-players=20", "max-players=50") + "view-distance=8\n"
+        );
+    }
+
+    #[test]
+    fn drift() {
+        let mut file = PropertiesFile::parse(FILE);
+        let written = BTreeMap::from([("max-players".to_string(), "10".to_string())]);
+        let report = file.sync(&managed(&[("max-players", "10")]), &written);
+        assert_eq!(
+            report.drift,
+            vec![Drift {
+                key: "max-players".to_string(),
+                expected: "10".to_string(),
+                found: "20".to_string(),
+            }]
+        );
+        assert_eq!(file.get("max-players"), Some("10"));
+    }
+
+    #[test]
+    fn escaping() {
+        let mut file = PropertiesFile::parse("motd=\\u00A7aHello\\: world\n");
+        assert_eq!(file.get("motd"), Some("§aHello: world"));
+        file.set("motd", " Привет=1");
+        assert_eq!(
+            file.render(),
+            "motd=\\ \\u041F\\u0440\\u0438\\u0432\\u0435\\u0442\\=1\n"
+        );
+        let file = PropertiesFile::parse(&file.render());
+        assert_eq!(file.get("motd"), Some(" Привет=1"));
+
+        let file = PropertiesFile::parse("level-seed = multi\\\n    line\n");
+        assert_eq!(file.get("level-seed"), Some("multiline"));
+    }
+}
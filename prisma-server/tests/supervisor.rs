@@ -0,0 +1,141 @@
+#[cfg(test)]
+#[cfg(unix)]
+mod supervisor {
+    use std::{future::Future, os::unix::fs::PermissionsExt, sync::Mutex, time::Duration};
+
+    use prisma_config::config::CoreOptions;
+    use prisma_server::{
+        launcher::Launcher,
+        supervisor::{ServerState, Supervisor, SupervisorEvent, SupervisorState},
+        ServerError,
+    };
+    use tempfile::TempDir;
+    use tokio::sync::mpsc;
+
+    /// Stopping sends `SIGTERM` to the whole test process, the tests take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// Supervisor of a fake `java` running `script`, with the state file in `dir`
+    fn supervisor(dir: &TempDir, script: &str) -> Supervisor {
+        let java = dir.path().join("java");
+        std::fs::write(&java, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let launcher = Launcher::new("server.jar", CoreOptions::default())
+            .with_java(java.to_string_lossy())
+            .with_dir(dir.path());
+        Supervisor::new(launcher).with_state_path(dir.path().join("server.ron"))
+    }
+
+    /// Runs on a runtime that doesn't wait for the console reader, it blocks on our stdin
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let output = runtime.block_on(future);
+        runtime.shutdown_background();
+        output
+    }
+
+    /// Content of the state file in `dir`
+    async fn state(dir: &TempDir) -> SupervisorState {
+        let path = dir.path().join("server.ron");
+        SupervisorState::load(&path.to_string_lossy())
+            .await
+            .unwrap()
+    }
+
+    /// Events sent so far
+    fn events(mut received: mpsc::UnboundedReceiver<SupervisorEvent>) -> Vec<SupervisorEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = received.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn restarts_with_backoff_until_crash_loop() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+        let (sender, received) = mpsc::unbounded_channel();
+        let supervisor = supervisor(&dir, "exit 3")
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(15))
+            .with_max_restarts(3)
+            .with_events(sender);
+
+        block_on(async {
+            let error = supervisor.run().await.unwrap_err();
+            assert!(matches!(
+                error,
+                ServerError::CrashLoop { restarts: 4, status } if status.code() == Some(3)
+            ));
+
+            let state = state(&dir).await;
+            assert_eq!(state.state, ServerState::Crashed);
+            assert_eq!(state.restarts, 4);
+            assert_eq!(state.last_exit, Some(3));
+            assert_eq!(state.pid, None);
+        });
+
+        let delays = events(received)
+            .into_iter()
+            .map(|x| match x {
+                SupervisorEvent::Restarting {
+                    delay, restarts, ..
+                } => (delay.as_millis(), restarts),
+                event => panic!("unexpected {:?}", event),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [(10, 1), (15, 2), (15, 3)]);
+    }
+
+    #[test]
+    fn kills_after_stop_timeout() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+        let (sender, received) = mpsc::unbounded_channel();
+        // ignores `stop` on its console
+        let supervisor = supervisor(&dir, "exec sleep 30")
+            .with_stop_timeout(Duration::from_millis(100))
+            .with_events(sender);
+
+        block_on(async {
+            let stop = async {
+                let path = dir.path().join("server.ron");
+                while !matches!(
+                    SupervisorState::load(&path.to_string_lossy()).await,
+                    Ok(SupervisorState {
+                        state: ServerState::Running,
+                        ..
+                    })
+                ) {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                terminate(std::process::id());
+            };
+            let (status, _) = tokio::join!(supervisor.run(), stop);
+            assert_eq!(status.unwrap().code(), None);
+
+            let state = state(&dir).await;
+            assert_eq!(state.state, ServerState::Stopped);
+            assert_eq!(state.restarts, 0);
+        });
+
+        assert_eq!(
+            events(received),
+            [SupervisorEvent::Killed {
+                timeout: Duration::from_millis(100)
+            }]
+        );
+    }
+
+    /// Sends `SIGTERM` to the process
+    fn terminate(pid: u32) {
+        let status = std::process::Command::new("kill")
+            .args(["-TERM", &pid.to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
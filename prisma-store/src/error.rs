@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use prisma_hash::HashError;
+use prisma_providers::ProviderError;
+
+/// Errors of installing, validating and repairing store items
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    /// Item couldn't be resolved
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// File operation failed
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Download couldn't be started or was interrupted
+    #[error("download of {url} failed: {source}")]
+    Download { url: String, source: reqwest::Error },
+    /// Downloaded file doesn't match the expected hash
+    #[error("download of {url}: {source}")]
+    Hash { url: String, source: HashError },
+    /// Loader installer or build tool failed
+    #[error("{tool} exited with {status}, see {}", log.display())]
+    Tool {
+        tool: String,
+        status: std::process::ExitStatus,
+        /// Tool output
+        log: PathBuf,
+    },
+    /// Build tool succeeded but produced no jar
+    #[error("{tool} produced no jar, see {}", log.display())]
+    NoOutput { tool: String, log: PathBuf },
+    /// File name can't be taken from the download url
+    #[error("invalid file name in {url}")]
+    InvalidUrl { url: String },
+    /// Store or plan file isn't valid
+    #[error("{}:{source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: ron::error::SpannedError,
+    },
+    /// Store or plan couldn't be serialized
+    #[error(transparent)]
+    Serialize(#[from] ron::Error),
+    /// Store was modified after the plan was built
+    #[error("store changed since the plan was built: {item}")]
+    PlanOutdated { item: String },
+    /// Plan installs an item without a resolved target
+    #[error("plan has no target for {item}")]
+    PlanIncomplete { item: String },
+    /// Lockfile has no entry or another version for a config entry
+    #[error("prisma.lock is out of date for {item}, run sync without --locked")]
+    LockOutdated { item: String },
+    /// Downloaded file doesn't have the locked hashes
+    #[error("{path} of {item} doesn't match prisma.lock")]
+    LockMismatch { item: String, path: String },
+    /// Locked jar of a build tool isn't in the shared store, a rebuild wouldn't match
+    #[error("cannot reproduce locked build {name} of {item}, its jar isn't in the shared store")]
+    LockedBuildMissing { item: String, name: String },
+    /// Generation to roll back to doesn't exist
+    #[error("generation {number} doesn't exist")]
+    GenerationNotFound { number: u32 },
+    /// Install task panicked or was cancelled
+    #[error("install task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+impl StoreError {
+    /// Maps an IO error of the given file
+    pub(crate) fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { path, source }
+    }
+}
@@ -0,0 +1,340 @@
+//! Numbered snapshots of the deployment.
+//!
+//! A generation holds the store and the lockfile it was made from, and a farm of links
+//! to the stored files. Deployed files link through the [CURRENT_GENERATION_PATH] link,
+//! so a single swap of it switches every file to another generation.
+use std::path::{Component, Path, PathBuf};
+
+use prisma_core::{time::now, CURRENT_GENERATION_PATH, STORE_GENERATIONS_DIR};
+use serde::{Deserialize, Serialize};
+
+use crate::{lock::Lock, shared::SharedStore, store::Store, StoreError};
+
+/// Store snapshot of a generation
+const STORE_FILE: &str = "store.ron";
+/// Lockfile snapshot of a generation
+const LOCK_FILE: &str = "prisma.lock";
+/// Description of a generation
+const INFO_FILE: &str = "generation.ron";
+/// Links to the stored files, laid out like the deployed ones
+const LINKS_DIR: &str = "links";
+
+/// Generations of an instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generations {
+    dir: PathBuf,
+    current: PathBuf,
+}
+
+/// Description of a generation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Generation {
+    pub number: u32,
+    /// Seconds since the unix epoch
+    pub created_at: u64,
+    /// Installed items, like `core Paper 1.20.1 (17)`
+    pub items: Vec<String>,
+}
+
+impl Default for Generations {
+    fn default() -> Self {
+        Self::new(STORE_GENERATIONS_DIR, CURRENT_GENERATION_PATH)
+    }
+}
+
+impl Generations {
+    /// Generations in `dir`, the active one is linked at `current`
+    pub fn new(dir: impl Into<PathBuf>, current: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            current: current.into(),
+        }
+    }
+
+    /// Every generation, oldest first
+    pub async fn list(&self) -> Result<Vec<Generation>, StoreError> {
+        let mut generations = Vec::new();
+        for number in self.numbers().await? {
+            let path = self.path(number).join(INFO_FILE);
+            let bytes = tokio::fs::read(&path)
+                .await
+                .map_err(StoreError::io(&path))?;
+            generations.push(
+                ron::de::from_bytes(&bytes).map_err(|source| StoreError::Parse {
+                    path: path.clone(),
+                    source,
+                })?,
+            );
+        }
+        Ok(generations)
+    }
+
+    /// Number of the active generation
+    pub async fn current(&self) -> Option<u32> {
+        let target = tokio::fs::read_link(&self.current).await.ok()?;
+        target.file_name()?.to_str()?.parse().ok()
+    }
+
+    /// Generation before the active one
+    pub async fn previous(&self) -> Result<Option<u32>, StoreError> {
+        let current = self.current().await;
+        Ok(self
+            .numbers()
+            .await?
+            .into_iter()
+            .filter(|x| current.is_none_or(|current| *x < current))
+            .max())
+    }
+
+    /// Snapshots the store and the lockfile as a new generation and activates it
+    ///
+    /// Nothing is made if the store is the one of the active generation. Generations
+    /// past the `keep` newest ones are deleted, `0` keeps all of them.
+    pub async fn commit(
+        &self,
+        store: &Store,
+        lock: &Lock,
+        keep: usize,
+    ) -> Result<Option<Generation>, StoreError> {
+        if let Some(current) = self.current().await {
+            if self.store(current).await.ok().as_ref() == Some(store) {
+                return Ok(None);
+            }
+        }
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(StoreError::io(&self.dir))?;
+        let number = self.numbers().await?.last().map_or(1, |x| x + 1);
+        let generation = Generation {
+            number,
+            created_at: now(),
+            items: store
+                .inner
+                .iter()
+                .map(|x| {
+                    format!(
+                        "{} {}",
+                        x.item.provider.describe(),
+                        x.item.version.describe()
+                    )
+                })
+                .collect(),
+        };
+
+        // made aside and renamed, a generation is never seen half written
+        let staging = self.dir.join(format!(".{}.tmp", number));
+        let _ = tokio::fs::remove_dir_all(&staging).await;
+        let made = async {
+            tokio::fs::create_dir_all(&staging)
+                .await
+                .map_err(StoreError::io(&staging))?;
+            store
+                .save(&staging.join(STORE_FILE).to_string_lossy())
+                .await?;
+            lock.save(&staging.join(LOCK_FILE).to_string_lossy())
+                .await?;
+            let info = staging.join(INFO_FILE);
+            tokio::fs::write(
+                &info,
+                ron::ser::to_string_pretty(&generation, Default::default())?,
+            )
+            .await
+            .map_err(StoreError::io(&info))?;
+            for (link, original) in entries(store) {
+                let farmed = staging.join(LINKS_DIR).join(farm_path(&link));
+                let original = tokio::fs::canonicalize(&original)
+                    .await
+                    .map_err(StoreError::io(&original))?;
+                replace_link(&original, &farmed).await?;
+            }
+            let path = self.path(number);
+            tokio::fs::rename(&staging, &path)
+                .await
+                .map_err(StoreError::io(&path))
+        }
+        .await;
+        if let Err(e) = made {
+            let _ = tokio::fs::remove_dir_all(&staging).await;
+            return Err(e);
+        }
+
+        // files of every kept generation stay in the shared store
+        SharedStore::global()
+            .register(&self.path(number).join(STORE_FILE).to_string_lossy())
+            .await?;
+        self.activate(number).await?;
+        self.prune(keep).await?;
+        Ok(Some(generation))
+    }
+
+    /// Activates generation `number`, its store and lockfile replace `store_path` and
+    /// `lock_path`
+    pub async fn rollback(
+        &self,
+        number: u32,
+        store_path: &str,
+        lock_path: &str,
+    ) -> Result<Generation, StoreError> {
+        let generation = self
+            .list()
+            .await?
+            .into_iter()
+            .find(|x| x.number == number)
+            .ok_or(StoreError::GenerationNotFound { number })?;
+        self.activate(number).await?;
+
+        let path = self.path(number);
+        for (file, target) in [(STORE_FILE, store_path), (LOCK_FILE, lock_path)] {
+            tokio::fs::copy(path.join(file), target)
+                .await
+                .map_err(StoreError::io(target))?;
+        }
+        Ok(generation)
+    }
+
+    /// Swaps the [CURRENT_GENERATION_PATH] link and links the deployed files through it
+    async fn activate(&self, number: u32) -> Result<(), StoreError> {
+        let previous = match self.current().await {
+            Some(current) => self.store(current).await.ok(),
+            None => None,
+        };
+        let store = self.store(number).await?;
+
+        let target =
+            std::path::absolute(self.path(number)).map_err(StoreError::io(self.path(number)))?;
+        replace_link(&target, &self.current).await?;
+
+        // through the link the farm of the active generation is used
+        let current = std::path::absolute(&self.current).map_err(StoreError::io(&self.current))?;
+        let deployed = entries(&store);
+        for (link, _) in &deployed {
+            replace_link(&current.join(LINKS_DIR).join(farm_path(link)), link).await?;
+        }
+
+        // files of the previous generation that this one doesn't have
+        for (link, _) in previous.as_ref().map(entries).unwrap_or_default() {
+            if deployed.iter().any(|(x, _)| x == &link) {
+                continue;
+            }
+            let ours = tokio::fs::read_link(&link)
+                .await
+                .is_ok_and(|x| x.starts_with(&current));
+            if ours {
+                tokio::fs::remove_file(&link)
+                    .await
+                    .map_err(StoreError::io(&link))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the oldest generations, except the active one
+    async fn prune(&self, keep: usize) -> Result<(), StoreError> {
+        if keep == 0 {
+            return Ok(());
+        }
+        let current = self.current().await;
+        let numbers = self.numbers().await?;
+        let old = numbers.len().saturating_sub(keep);
+        for number in numbers.into_iter().take(old) {
+            if Some(number) == current {
+                continue;
+            }
+            let path = self.path(number);
+            tokio::fs::remove_dir_all(&path)
+                .await
+                .map_err(StoreError::io(&path))?;
+        }
+        Ok(())
+    }
+
+    /// Store snapshot of generation `number`
+    async fn store(&self, number: u32) -> Result<Store, StoreError> {
+        Store::load(&self.path(number).join(STORE_FILE).to_string_lossy()).await
+    }
+
+    fn path(&self, number: u32) -> PathBuf {
+        self.dir.join(number.to_string())
+    }
+
+    /// Numbers of the generations, sorted
+    async fn numbers(&self) -> Result<Vec<u32>, StoreError> {
+        let mut numbers = Vec::new();
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(numbers),
+            Err(e) => return Err(StoreError::io(&self.dir)(e)),
+        };
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(StoreError::io(&self.dir))?
+        {
+            if let Some(number) = entry.file_name().to_str().and_then(|x| x.parse().ok()) {
+                numbers.push(number);
+            }
+        }
+        numbers.sort();
+        Ok(numbers)
+    }
+}
+
+/// Deployed links of the store and the files they point to, libraries included
+fn entries(store: &Store) -> Vec<(String, String)> {
+    store
+        .inner
+        .iter()
+        .flat_map(|item| {
+            let libraries = item.launch.iter().flat_map(|x| &x.libraries);
+            std::iter::once((item.symbol_link.clone(), item.path.clone()))
+                .chain(libraries.map(|x| (x.symbol_link.clone(), x.path.clone())))
+        })
+        .collect()
+}
+
+/// Path of a deployed link inside the farm, like `plugins/LuckPerms.jar`
+fn farm_path(link: &str) -> PathBuf {
+    Path::new(link)
+        .components()
+        .filter_map(|x| match x {
+            Component::Normal(x) => Some(x),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Points `link` to `original` with a single rename, whatever was there before
+async fn replace_link(original: &Path, link: impl AsRef<Path>) -> Result<(), StoreError> {
+    let link = link.as_ref();
+    if tokio::fs::read_link(link)
+        .await
+        .is_ok_and(|x| x == original)
+    {
+        return Ok(());
+    }
+    if let Some(parent) = link.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(StoreError::io(parent))?;
+    }
+
+    let mut temp = link.as_os_str().to_owned();
+    temp.push(format!(".{}.tmp", std::process::id()));
+    let temp = PathBuf::from(temp);
+    let _ = tokio::fs::remove_file(&temp).await;
+    #[cfg(unix)]
+    let linked = tokio::fs::symlink(original, &temp).await;
+    #[cfg(windows)]
+    let linked = match tokio::fs::metadata(original)
+        .await
+        .is_ok_and(|x| x.is_dir())
+    {
+        true => tokio::fs::symlink_dir(original, &temp).await,
+        false => tokio::fs::symlink_file(original, &temp).await,
+    };
+    linked.map_err(StoreError::io(&temp))?;
+    tokio::fs::rename(&temp, link)
+        .await
+        .map_err(StoreError::io(link))
+}
@@ -1 +1,9 @@
+pub mod error;
+pub mod generation;
+pub mod lock;
+pub mod plan;
+pub mod progress;
+pub mod shared;
 pub mod store;
+mod transaction;
+pub use error::StoreError;
@@ -0,0 +1,219 @@
+//! `prisma.lock`, the exact resolved set of the config.
+//!
+//! Unlike the store it has no deployment details, it is meant to be committed next to the
+//! config so that every machine installs byte-identical files.
+use prisma_core::{extension::ExtensionType, item::Item, provider::Provider, version::Version};
+use prisma_hash::HashType;
+use prisma_providers::{Build, DownloadMeta, Launch};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    store::{stage_all, Store, StoreItem},
+    StoreError,
+};
+
+/// Content of [LOCK_PATH](prisma_core::LOCK_PATH)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lock {
+    /// Core first, then extensions in config order
+    pub entries: Vec<LockEntry>,
+}
+
+/// Resolved state of a config entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockEntry {
+    /// Config entry, with the platform and extension provider
+    pub provider: Provider,
+    /// Resolved versions
+    pub version: Version,
+    /// Link of the downloaded file
+    pub url: String,
+    /// Hashes of the installed file, the one published by the provider first
+    pub hashes: Vec<HashType>,
+    /// Base URL of the provider that resolved it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<Launch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<Build>,
+}
+
+impl Lock {
+    /// Locks the installed state of the items, items that aren't installed are left out
+    pub async fn from_store(store: &Store, items: &[Item]) -> Result<Self, StoreError> {
+        let mut entries = Vec::with_capacity(items.len());
+        for installed in items.iter().filter_map(|x| store.find(&x.provider)) {
+            entries.push(LockEntry::from_store_item(installed).await?);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Entry of the config entry
+    pub fn find(&self, provider: &Provider) -> Option<&LockEntry> {
+        self.entries.iter().find(|x| &x.provider == provider)
+    }
+
+    /// Drops the entry of the mod or plugin `name`, like `kind`
+    pub fn remove_extension(&mut self, name: &str, kind: &ExtensionType) -> Option<LockEntry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|x| x.provider.is_extension_named(name, kind))?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Checks that every item is locked with the versions it pins
+    pub fn check(&self, items: &[Item]) -> Result<(), StoreError> {
+        for item in items {
+            match self.find(&item.provider) {
+                Some(entry) if entry.satisfies(&item.version) => {}
+                _ => {
+                    return Err(StoreError::LockOutdated {
+                        item: item.provider.describe(),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads a lockfile
+    pub async fn load(path: &str) -> Result<Self, StoreError> {
+        let bytes = tokio::fs::read(path).await.map_err(StoreError::io(path))?;
+        ron::de::from_bytes(&bytes).map_err(|source| StoreError::Parse {
+            path: path.into(),
+            source,
+        })
+    }
+
+    /// Saves the lockfile, it isn't written if nothing changed
+    pub async fn save(&self, path: &str) -> Result<(), StoreError> {
+        let text = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+        if tokio::fs::read_to_string(path).await.ok().as_deref() == Some(text.as_str()) {
+            return Ok(());
+        }
+        tokio::fs::write(path, text)
+            .await
+            .map_err(StoreError::io(path))
+    }
+}
+
+impl LockEntry {
+    /// Entry of an installed item, the file is hashed again with sha256
+    pub async fn from_store_item(installed: &StoreItem) -> Result<Self, StoreError> {
+        let bytes = tokio::fs::read(&installed.path)
+            .await
+            .map_err(StoreError::io(&installed.path))?;
+        let sha256 = HashType::new_sha256(HashType::compute_sha256(bytes));
+        let hashes =
+            [installed.hash.clone(), sha256]
+                .into_iter()
+                .fold(Vec::new(), |mut hashes, hash| {
+                    if hash != HashType::None && !hashes.contains(&hash) {
+                        hashes.push(hash);
+                    }
+                    hashes
+                });
+
+        Ok(Self {
+            provider: installed.item.provider.clone(),
+            version: installed.item.version.clone(),
+            url: installed.url.clone(),
+            hashes,
+            base: installed.base.clone(),
+            launch: installed.launch.as_ref().map(Launch::from),
+            build: installed.build.clone(),
+        })
+    }
+
+    /// Every pinned version of `version` is the locked one, see [Version::satisfies]
+    pub fn satisfies(&self, version: &Version) -> bool {
+        self.version.satisfies(version)
+    }
+
+    /// Installed item is the locked one
+    pub async fn matches(&self, installed: &StoreItem) -> bool {
+        installed.url == self.url
+            && installed.item.version == self.version
+            && self.verify(&installed.path).await
+    }
+
+    /// File has every locked hash
+    pub async fn verify(&self, path: &str) -> bool {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => self.hashes.iter().all(|x| x.compare(&bytes).is_ok()),
+            Err(_) => false,
+        }
+    }
+
+    /// Download of the entry, without resolving it again
+    ///
+    /// Downloads are checked with the first hash. A build tool isn't run, the jar is taken
+    /// from the shared store by its sha256.
+    pub fn meta(&self) -> DownloadMeta {
+        let hash = match &self.build {
+            Some(_) => self
+                .hashes
+                .iter()
+                .find(|x| matches!(x, HashType::SHA256(_))),
+            None => self.hashes.first(),
+        };
+        DownloadMeta {
+            download_link: self.url.clone(),
+            hash: hash.cloned().unwrap_or(HashType::None),
+            game_version: self.version.game_version.clone().unwrap_or_default(),
+            version_build: self.version.version_build.clone(),
+            loader_version: self.version.loader_version.clone(),
+            installer_version: self.version.installer_version.clone(),
+            launch: self.launch.clone(),
+            build: self.build.clone(),
+            base: self.base.clone(),
+        }
+    }
+}
+
+impl Store {
+    /// Installs the locked set of the items, nothing is resolved
+    ///
+    /// Items that differ from the lock are staged and checked with every locked hash
+    /// before any of them replaces the installed one, then saved with the store. On any
+    /// mismatch nothing is changed. Returns the items that were installed.
+    pub async fn install_locked(
+        &mut self,
+        items: &[Item],
+        lock: &Lock,
+    ) -> Result<Vec<Provider>, StoreError> {
+        lock.check(items)?;
+        let mut outdated = Vec::new();
+        for item in items {
+            let Some(entry) = lock.find(&item.provider) else {
+                continue;
+            };
+            if let Some(current) = self.find(&item.provider) {
+                if entry.matches(current).await {
+                    continue;
+                }
+            }
+            outdated.push((item.clone(), Some(entry.meta())));
+        }
+
+        let prepared = stage_all(outdated).await?;
+        for x in &prepared {
+            let path = x.staged.path().to_string_lossy().to_string();
+            let verified = match lock.find(&x.item.provider) {
+                Some(entry) => entry.verify(&path).await,
+                None => false,
+            };
+            if !verified {
+                return Err(StoreError::LockMismatch {
+                    item: x.item.provider.describe(),
+                    path,
+                });
+            }
+        }
+        self.commit_prepared(&[], &prepared).await?;
+        Ok(prepared.into_iter().map(|x| x.item.provider).collect())
+    }
+}
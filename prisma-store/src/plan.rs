@@ -0,0 +1,336 @@
+//! Plan of changes between the config and the store.
+//!
+//! A [Plan] is built once, can be reviewed or saved, and is then applied as is,
+//! without resolving anything again.
+use std::cmp::Ordering;
+
+use prisma_config::config::Config;
+use prisma_core::{item::Item, version::Version, STORE_PATH};
+use prisma_hash::HashType;
+use prisma_providers::{Build, DownloadMeta, Launch, ProviderError};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    store::{stage_all, Store, StoreItem},
+    StoreError,
+};
+
+/// What will happen to a single item
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::AsRefStr,
+    strum::EnumIs,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Action {
+    /// Not installed yet
+    Add,
+    /// Installed, a newer version is resolved
+    Upgrade,
+    /// Installed, an older version is resolved
+    Downgrade,
+    /// Installed, the same file is installed again for `force_update`
+    Reinstall,
+    /// Installed, but not declared in the config anymore
+    Remove,
+    /// Installed and up to date, or frozen
+    Unchanged,
+}
+
+/// Resolved state of an item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resolved {
+    pub game_version: Option<String>,
+    pub version_build: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loader_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installer_version: Option<String>,
+    pub url: String,
+    pub hash: HashType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<Launch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<Build>,
+    /// Base URL of the provider that resolved it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+}
+
+/// Change of a single item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub action: Action,
+    /// Item as declared in the config, or as installed for [Action::Remove]
+    pub item: Item,
+    /// State in the store
+    pub old: Option<Resolved>,
+    /// State after apply
+    pub new: Option<Resolved>,
+}
+
+/// Ordered list of changes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+    /// [Store::digest] of the store the plan was built from
+    #[serde(default)]
+    pub store: String,
+}
+
+impl From<&StoreItem> for Resolved {
+    fn from(value: &StoreItem) -> Self {
+        Self {
+            game_version: value.item.version.game_version.clone(),
+            version_build: value.item.version.version_build.clone(),
+            loader_version: value.item.version.loader_version.clone(),
+            installer_version: value.item.version.installer_version.clone(),
+            url: value.url.clone(),
+            hash: value.hash.clone(),
+            launch: value.launch.as_ref().map(Launch::from),
+            build: value.build.clone(),
+            base: value.base.clone(),
+        }
+    }
+}
+
+impl Resolved {
+    /// Every pinned version of `version` is the resolved one, see [Version::satisfies]
+    pub fn satisfies(&self, version: &Version) -> bool {
+        let resolved = Version {
+            game_version: self.game_version.clone(),
+            version_build: self.version_build.clone(),
+            loader_version: self.loader_version.clone(),
+            ..Default::default()
+        };
+        resolved.satisfies(version)
+    }
+
+    /// Same link, or the same hashed file from another mirror
+    fn same_file(&self, other: &Resolved) -> bool {
+        self.url == other.url || (self.hash != HashType::None && self.hash == other.hash)
+    }
+}
+
+impl From<DownloadMeta> for Resolved {
+    fn from(value: DownloadMeta) -> Self {
+        Self {
+            game_version: Some(value.game_version),
+            version_build: value.version_build,
+            loader_version: value.loader_version,
+            installer_version: value.installer_version,
+            url: value.download_link,
+            hash: value.hash,
+            launch: value.launch,
+            build: value.build,
+            base: value.base,
+        }
+    }
+}
+
+impl From<Resolved> for DownloadMeta {
+    fn from(value: Resolved) -> Self {
+        Self {
+            download_link: value.url,
+            hash: value.hash,
+            game_version: value.game_version.unwrap_or_default(),
+            version_build: value.version_build,
+            loader_version: value.loader_version,
+            installer_version: value.installer_version,
+            launch: value.launch,
+            build: value.build,
+            base: value.base,
+        }
+    }
+}
+
+impl Plan {
+    /// Resolves every item of the config and compares it with the store
+    ///
+    /// Frozen items aren't resolved unless `force_update` is set.
+    pub async fn build(config: &Config, store: &Store) -> Result<Self, StoreError> {
+        let items = config.clone().normolise().items();
+        let mut changes = Vec::with_capacity(items.len());
+
+        for item in &items {
+            let old = store.find(&item.provider).map(Resolved::from);
+            let frozen = item.options.freeze && !item.options.force_update;
+
+            let change = match old {
+                Some(old) if frozen => Change {
+                    action: Action::Unchanged,
+                    item: item.clone(),
+                    new: Some(old.clone()),
+                    old: Some(old),
+                },
+                old => {
+                    let new = match (DownloadMeta::fetch(item).await, &old) {
+                        (Ok(meta), _) => Resolved::from(meta),
+                        // offline, the installed version is kept if it is still the requested one
+                        (Err(ProviderError::NotCached { .. }), Some(old))
+                            if old.satisfies(&item.version) =>
+                        {
+                            old.clone()
+                        }
+                        (Err(e), _) => return Err(e.into()),
+                    };
+                    let action = match &old {
+                        None => Action::Add,
+                        Some(old) if old.same_file(&new) => match item.options.force_update {
+                            true => Action::Reinstall,
+                            false => Action::Unchanged,
+                        },
+                        Some(old) => Action::between(old, &new),
+                    };
+                    Change {
+                        action,
+                        item: item.clone(),
+                        old,
+                        new: Some(new),
+                    }
+                }
+            };
+            changes.push(change);
+        }
+
+        // Installed, but not declared anymore
+        changes.extend(
+            store
+                .inner
+                .iter()
+                .filter(|x| !items.iter().any(|item| item.provider == x.item.provider))
+                .map(|x| Change {
+                    action: Action::Remove,
+                    item: x.item.clone(),
+                    old: Some(Resolved::from(x)),
+                    new: None,
+                }),
+        );
+
+        Ok(Self {
+            changes,
+            store: store.digest()?,
+        })
+    }
+
+    /// Plan has nothing to do
+    pub fn is_empty(&self) -> bool {
+        self.changes.iter().all(|x| x.action.is_unchanged())
+    }
+
+    /// Loads a saved plan
+    pub async fn load(path: &str) -> Result<Self, StoreError> {
+        let bytes = tokio::fs::read(path).await.map_err(StoreError::io(path))?;
+        ron::de::from_bytes(&bytes).map_err(|source| StoreError::Parse {
+            path: path.into(),
+            source,
+        })
+    }
+
+    /// Saves the plan to apply it later
+    pub async fn save(&self, path: &str) -> Result<(), StoreError> {
+        tokio::fs::write(
+            path,
+            ron::ser::to_string_pretty(&self, ron::ser::PrettyConfig::default())?,
+        )
+        .await
+        .map_err(StoreError::io(path))?;
+        Ok(())
+    }
+}
+
+impl Action {
+    /// Compares two resolved states of the same item by game version, then by build and loader
+    ///
+    /// Versions that can't be compared (like Modrinth version ids) are an upgrade.
+    pub fn between(old: &Resolved, new: &Resolved) -> Self {
+        let ordering = compare_version(old.game_version.as_deref(), new.game_version.as_deref())
+            .then_with(|| {
+                compare_version(old.version_build.as_deref(), new.version_build.as_deref())
+            })
+            .then_with(|| {
+                compare_version(old.loader_version.as_deref(), new.loader_version.as_deref())
+            });
+        match ordering {
+            Ordering::Greater => Action::Downgrade,
+            _ => Action::Upgrade,
+        }
+    }
+}
+
+/// Compares dotted numeric versions like `1.20.1`, anything else is equal
+fn compare_version(old: Option<&str>, new: Option<&str>) -> Ordering {
+    let parse = |v: &str| {
+        v.split('.')
+            .map(|x| x.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+    };
+    match (old.and_then(parse), new.and_then(parse)) {
+        (Some(old), Some(new)) => old.cmp(&new),
+        _ => Ordering::Equal,
+    }
+}
+
+impl Store {
+    /// Applies a plan exactly as it was built and saves the store
+    ///
+    /// Fails without changes if the store was modified after the plan was built. Every
+    /// file is downloaded before anything is deployed, if any step fails the deployed files
+    /// and the store are left as they were.
+    pub async fn apply(&mut self, plan: &Plan) -> Result<(), StoreError> {
+        for change in &plan.changes {
+            let current = self.find(&change.item.provider).map(Resolved::from);
+            if current != change.old {
+                return Err(StoreError::PlanOutdated {
+                    item: change.item.provider.describe(),
+                });
+            }
+        }
+        // items the plan doesn't list, or other fields of the planned ones
+        if self.digest()? != plan.store {
+            let unplanned = self.inner.iter().find(|x| {
+                !plan
+                    .changes
+                    .iter()
+                    .any(|change| change.item.provider == x.item.provider)
+            });
+            return Err(StoreError::PlanOutdated {
+                item: match unplanned {
+                    Some(x) => x.item.provider.describe(),
+                    None => STORE_PATH.to_string(),
+                },
+            });
+        }
+
+        let mut removed = Vec::new();
+        let mut items = Vec::new();
+        for change in &plan.changes {
+            match change.action {
+                Action::Unchanged => {}
+                Action::Remove => removed.push(change.item.provider.clone()),
+                Action::Add | Action::Upgrade | Action::Downgrade | Action::Reinstall => {
+                    let new = change
+                        .new
+                        .clone()
+                        .ok_or_else(|| StoreError::PlanIncomplete {
+                            item: change.item.provider.describe(),
+                        })?;
+                    items.push((change.item.clone(), Some(new.into())));
+                }
+            }
+        }
+
+        // everything is downloaded before the first link changes
+        let prepared = stage_all(items).await?;
+        self.commit_prepared(&removed, &prepared).await
+    }
+}
@@ -0,0 +1,116 @@
+//! Progress of the downloads made by installs.
+//!
+//! Every download reports to a single broadcast channel, [subscribe] to render progress
+//! bars or log transfers. A receiver that falls behind skips the oldest events.
+use std::sync::LazyLock;
+
+use tokio::sync::broadcast;
+
+/// Events kept for receivers that fall behind
+const CAPACITY: usize = 1024;
+/// Bytes between two [Progress::Advanced] events of a download
+const STEP: u64 = 256 * 1024;
+
+static SENDER: LazyLock<broadcast::Sender<Progress>> =
+    LazyLock::new(|| broadcast::channel(CAPACITY).0);
+
+/// Progress of a download
+///
+/// `item` describes the item the file belongs to, like `core Paper`. An item may download
+/// several files, they are told apart by `url`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Progress {
+    /// Server answered, `total` is its content length when known
+    Started {
+        item: String,
+        url: String,
+        total: Option<u64>,
+    },
+    /// `done` bytes were written to disk
+    Advanced {
+        item: String,
+        url: String,
+        done: u64,
+        total: Option<u64>,
+    },
+    /// Whole file was written and its hash matched
+    Finished {
+        item: String,
+        url: String,
+        bytes: u64,
+    },
+    /// Download was interrupted or the file was rejected
+    Failed { item: String, url: String },
+}
+
+/// Receives the progress of every download started from now on
+pub fn subscribe() -> broadcast::Receiver<Progress> {
+    SENDER.subscribe()
+}
+
+/// Download being reported, reports [Progress::Failed] if dropped before finishing
+pub(crate) struct Transfer {
+    item: String,
+    url: String,
+    total: Option<u64>,
+    done: u64,
+    reported: u64,
+    finished: bool,
+}
+
+impl Transfer {
+    pub(crate) fn start(item: String, url: String, total: Option<u64>) -> Self {
+        send(Progress::Started {
+            item: item.clone(),
+            url: url.clone(),
+            total,
+        });
+        Self {
+            item,
+            url,
+            total,
+            done: 0,
+            reported: 0,
+            finished: false,
+        }
+    }
+
+    /// Counts `bytes` more written
+    pub(crate) fn advance(&mut self, bytes: u64) {
+        self.done += bytes;
+        if self.done - self.reported >= STEP {
+            self.reported = self.done;
+            send(Progress::Advanced {
+                item: self.item.clone(),
+                url: self.url.clone(),
+                done: self.done,
+                total: self.total,
+            });
+        }
+    }
+
+    pub(crate) fn finish(mut self) {
+        self.finished = true;
+        send(Progress::Finished {
+            item: self.item.clone(),
+            url: self.url.clone(),
+            bytes: self.done,
+        });
+    }
+}
+
+impl Drop for Transfer {
+    fn drop(&mut self) {
+        if !self.finished {
+            send(Progress::Failed {
+                item: self.item.clone(),
+                url: self.url.clone(),
+            });
+        }
+    }
+}
+
+/// Sends to the receivers, if any
+fn send(progress: Progress) {
+    let _ = SENDER.send(progress);
+}
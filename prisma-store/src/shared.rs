@@ -0,0 +1,361 @@
+//! Content-addressed store shared by every instance on the host.
+//!
+//! Files are kept once as `<sha256>-<name>` and linked into each instance. Instances register
+//! their `store.ron` as a root, [SharedStore::collect_garbage] only deletes files that no
+//! root references.
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::RwLock,
+    time::{Duration, SystemTime},
+};
+
+use prisma_core::STORE_DIR;
+use prisma_hash::{HashType, Hasher};
+use tokio::io::AsyncReadExt;
+
+use crate::{store::Store, StoreError};
+
+static GLOBAL: RwLock<Option<SharedStore>> = RwLock::new(None);
+
+/// Overrides the location of the shared store
+pub const STORE_DIR_ENV: &str = "PRISMA_STORE_DIR";
+/// Directory of the registered instances, inside the store
+const ROOTS_DIR: &str = ".roots";
+/// Files younger than this are kept by default, an instance may be installing them
+const GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// Directory of content-addressed files
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedStore {
+    dir: PathBuf,
+    /// Age under which unused files are kept
+    grace: Duration,
+}
+
+impl SharedStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            grace: GRACE,
+        }
+    }
+
+    /// Keeps unused files younger than `grace` on garbage collection
+    pub fn with_grace(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    /// Store of the host, `$PRISMA_STORE_DIR` or `$XDG_DATA_HOME/prisma/store`,
+    /// `~/.local/share/prisma/store` by default
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var_os(name).filter(|x| !x.is_empty());
+        let dir = match (var(STORE_DIR_ENV), var("XDG_DATA_HOME"), var("HOME")) {
+            (Some(dir), _, _) => PathBuf::from(dir),
+            (None, Some(data), _) => PathBuf::from(data).join("prisma/store"),
+            (None, None, Some(home)) => PathBuf::from(home).join(".local/share/prisma/store"),
+            (None, None, None) => match var("APPDATA") {
+                Some(data) => PathBuf::from(data).join("prisma/store"),
+                None => PathBuf::from(STORE_DIR).join("shared"),
+            },
+        };
+        Self::new(dir)
+    }
+
+    /// Store used by installs, [SharedStore::from_env] unless replaced
+    pub fn global() -> SharedStore {
+        if let Some(store) = GLOBAL.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            return store.clone();
+        }
+        GLOBAL
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(Self::from_env)
+            .clone()
+    }
+
+    /// Replaces the global store
+    pub fn set_global(store: SharedStore) {
+        *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(store);
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Creates the store directory
+    pub async fn create(&self) -> Result<(), StoreError> {
+        let roots = self.dir.join(ROOTS_DIR);
+        tokio::fs::create_dir_all(&roots)
+            .await
+            .map_err(StoreError::io(&roots))
+    }
+
+    /// Path of a file with the given sha256
+    pub fn path(&self, sha256: &str, name: &str) -> PathBuf {
+        self.dir.join(format!("{}-{}", sha256, name))
+    }
+
+    /// Moves `file` into the store as `<sha256>-<name>`
+    ///
+    /// A file with the same content is reused and `file` is dropped. Returns the path in
+    /// the store and the sha256 of the content.
+    pub async fn add(&self, file: &Path, name: &str) -> Result<(PathBuf, HashType), StoreError> {
+        let sha256 = sha256(file).await?;
+        let path = self.path(&sha256, name);
+        let hash = HashType::new_sha256(sha256);
+
+        if is_stored(&path, &hash).await {
+            let _ = tokio::fs::remove_file(file).await;
+            return Ok((path, hash));
+        }
+
+        self.create().await?;
+        // the store may be on another filesystem, the file is renamed into place from
+        // a copy next to it so that other instances never see it half written
+        if tokio::fs::rename(file, &path).await.is_err() {
+            let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+            tokio::fs::copy(file, &temp)
+                .await
+                .map_err(StoreError::io(&temp))?;
+            tokio::fs::rename(&temp, &path)
+                .await
+                .map_err(StoreError::io(&path))?;
+            let _ = tokio::fs::remove_file(file).await;
+        }
+        Ok((path, hash))
+    }
+
+    /// File of the store whose name ends with `-<name>`, if any
+    pub async fn find(&self, name: &str) -> Option<PathBuf> {
+        let suffix = format!("-{}", name);
+        self.files()
+            .await
+            .ok()?
+            .into_iter()
+            .find(|x| x.ends_with(&suffix))
+            .map(|x| self.dir.join(x))
+    }
+
+    /// File of the store with the given sha256, if any
+    pub async fn find_sha256(&self, sha256: &str) -> Option<PathBuf> {
+        let prefix = format!("{}-", sha256);
+        self.files()
+            .await
+            .ok()?
+            .into_iter()
+            .find(|x| x.starts_with(&prefix))
+            .map(|x| self.dir.join(x))
+    }
+
+    /// Files that may hold the content of `file_name`, like `<sha256>-<name>` or `<name>`
+    ///
+    /// Those are the stored files with the same sha256, or with the same name if it isn't
+    /// known, then the files of the registered instances with the same name. They aren't
+    /// checked, any of them may be corrupted as well.
+    pub async fn copies(&self, file_name: &str, sha256: Option<&str>) -> Vec<PathBuf> {
+        let (named_sha256, name) = split_name(file_name);
+        let mut copies = Vec::new();
+        for stored in self.files().await.unwrap_or_default() {
+            let found = match (sha256.or(named_sha256), split_name(&stored)) {
+                (Some(sha256), (Some(x), _)) => x == sha256,
+                (None, (_, x)) => x == name,
+                _ => false,
+            };
+            if found {
+                copies.push(self.dir.join(stored));
+            }
+        }
+
+        for (target, store) in self.stores().await.unwrap_or_default() {
+            // paths of an instance are relative to the directory of its `.prisma`
+            let instance = target
+                .ancestors()
+                .find(|x| x.file_name().is_some_and(|x| x == ".prisma"))
+                .and_then(Path::parent);
+            for item in &store.inner {
+                let path = Path::new(&item.path);
+                let same = path
+                    .file_name()
+                    .is_some_and(|x| split_name(&x.to_string_lossy()).1 == name);
+                let path = match instance {
+                    Some(instance) if path.is_relative() => instance.join(path),
+                    _ => path.to_path_buf(),
+                };
+                if same && !copies.contains(&path) {
+                    copies.push(path);
+                }
+            }
+        }
+        copies
+    }
+
+    /// Registers the `store.ron` of an instance, its files are kept by garbage collection
+    pub async fn register(&self, store_path: &str) -> Result<(), StoreError> {
+        let target = std::path::absolute(store_path).map_err(StoreError::io(store_path))?;
+        let root = self.root(&target);
+        if tokio::fs::read_link(&root).await.is_ok_and(|x| x == target) {
+            return Ok(());
+        }
+
+        self.create().await?;
+        let _ = tokio::fs::remove_file(&root).await;
+        #[cfg(unix)]
+        let linked = tokio::fs::symlink(&target, &root).await;
+        #[cfg(windows)]
+        let linked = tokio::fs::symlink_file(&target, &root).await;
+        linked.map_err(StoreError::io(&root))
+    }
+
+    /// Deletes the files that no registered instance uses
+    ///
+    /// Roots of instances that don't exist anymore are dropped. Returns the deleted files.
+    pub async fn collect_garbage(&self) -> Result<Vec<PathBuf>, StoreError> {
+        let garbage = self.garbage().await?;
+        for path in &garbage {
+            tokio::fs::remove_file(path)
+                .await
+                .map_err(StoreError::io(path))?;
+        }
+        Ok(garbage)
+    }
+
+    /// Files that no registered instance uses
+    pub async fn garbage(&self) -> Result<Vec<PathBuf>, StoreError> {
+        let used = self.used().await?;
+        let mut garbage = Vec::new();
+        for name in self.files().await? {
+            let path = self.dir.join(&name);
+            if !used.contains(&name) && !is_recent(&path, self.grace).await {
+                garbage.push(path);
+            }
+        }
+        Ok(garbage)
+    }
+
+    /// Names of the files referenced by the registered instances
+    async fn used(&self) -> Result<BTreeSet<String>, StoreError> {
+        let mut used = BTreeSet::new();
+        for (_, store) in self.stores().await? {
+            used.extend(
+                store
+                    .inner
+                    .iter()
+                    .filter_map(|x| Path::new(&x.path).file_name())
+                    .map(|x| x.to_string_lossy().to_string()),
+            );
+        }
+        Ok(used)
+    }
+
+    /// Stores of the registered instances, with the path of their `store.ron`
+    ///
+    /// Roots of instances that don't exist anymore are dropped.
+    async fn stores(&self) -> Result<Vec<(PathBuf, Store)>, StoreError> {
+        let mut stores = Vec::new();
+        let roots = self.dir.join(ROOTS_DIR);
+        let mut entries = match tokio::fs::read_dir(&roots).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(stores),
+            Err(e) => return Err(StoreError::io(&roots)(e)),
+        };
+        while let Some(entry) = entries.next_entry().await.map_err(StoreError::io(&roots))? {
+            let root = entry.path();
+            let Ok(target) = tokio::fs::read_link(&root).await else {
+                continue;
+            };
+            // the instance is gone along with its store directory
+            let instance = target.parent().unwrap_or(&target);
+            if !tokio::fs::try_exists(instance).await.unwrap_or(true) {
+                let _ = tokio::fs::remove_file(&root).await;
+                continue;
+            }
+            // nothing is installed yet
+            if !tokio::fs::try_exists(&target).await.unwrap_or(true) {
+                continue;
+            }
+            // an unreadable store keeps everything, nothing is known to be unused
+            let store = Store::load(&target.to_string_lossy()).await?;
+            stores.push((target, store));
+        }
+        Ok(stores)
+    }
+
+    /// Root of an instance, named by the hash of its `store.ron` path
+    fn root(&self, target: &Path) -> PathBuf {
+        let id = HashType::compute_sha256(target.to_string_lossy().as_bytes());
+        self.dir.join(ROOTS_DIR).join(id)
+    }
+
+    /// Names of the stored files, without the roots and unfinished copies
+    async fn files(&self) -> Result<Vec<String>, StoreError> {
+        let mut files = Vec::new();
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+            Err(e) => return Err(StoreError::io(&self.dir)(e)),
+        };
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(StoreError::io(&self.dir))?
+        {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_file = entry.file_type().await.is_ok_and(|x| x.is_file());
+            if is_file && !name.starts_with('.') && !name.ends_with(".tmp") {
+                files.push(name);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Sha256 and name of a stored file name, `<sha256>-<name>`, or just the name of another one
+fn split_name(file_name: &str) -> (Option<&str>, &str) {
+    match file_name.split_once('-') {
+        Some((sha256, name))
+            if sha256.len() == 64 && sha256.bytes().all(|x| x.is_ascii_hexdigit()) =>
+        {
+            (Some(sha256), name)
+        }
+        _ => (None, file_name),
+    }
+}
+
+/// Sha256 of a file, read in chunks
+async fn sha256(path: &Path) -> Result<String, StoreError> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(StoreError::io(path))?;
+    let mut hasher = Hasher::sha256();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await.map_err(StoreError::io(path))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.digest().unwrap_or_default())
+}
+
+/// File at `path` exists with the content of `hash`
+async fn is_stored(path: &Path, hash: &HashType) -> bool {
+    sha256(path)
+        .await
+        .is_ok_and(|x| HashType::new_sha256(x) == *hash)
+}
+
+/// File was modified within `grace`
+async fn is_recent(path: &Path, grace: Duration) -> bool {
+    tokio::fs::metadata(path)
+        .await
+        .and_then(|x| x.modified())
+        .is_ok_and(|x| {
+            SystemTime::now()
+                .duration_since(x)
+                .is_ok_and(|age| age < grace)
+        })
+}
@@ -1,19 +1,26 @@
 /// Store module provides functionality for managing game files and extensions
 /// through a centralized storage system with validation and repair capabilities.
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+};
 
 use prisma_core::{
-    extension::ExtensionType, item::Item, provider::Provider, CORE_DIR, MODS_DIR, PLUGINS_DIR,
-    STORE_CORES_DIR, STORE_MODS_DIR, STORE_PATH, STORE_PLUGINS_DIR, STORE_TEMP_DIR,
+    extension::ExtensionType, item::Item, platform::Platform, provider::Provider, CORE_DIR,
+    LIBRARIES_DIR, MODS_DIR, PLUGINS_DIR, STORE_CORES_DIR, STORE_DIR, STORE_EXTENSIONS_DIR,
+    STORE_LIBRARIES_DIR, STORE_LOGS_DIR, STORE_MODS_DIR, STORE_PATH, STORE_PLUGINS_DIR,
+    STORE_TEMP_DIR,
 };
 use prisma_hash::HashType;
-use prisma_providers::DownloadMeta;
+use prisma_providers::{Build, Client, DownloadMeta, Launch, Library, Registry};
 use ron::ser::PrettyConfig;
-use tempfile::Builder;
-use tokio::{fs::File, io::AsyncWriteExt, sync::Mutex};
+use tempfile::{Builder, TempDir};
+use tokio::{fs::File, io::AsyncWriteExt, task::JoinSet};
+
+use crate::{progress::Transfer, shared::SharedStore, transaction::Transaction, StoreError};
 
 /// Main store struct that holds all managed items
-#[derive(Debug, Clone, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize)]
 pub struct Store {
     /// Vector of all store items
     pub inner: Vec<StoreItem>,
@@ -32,31 +39,257 @@ pub struct StoreItem {
     pub symbol_link: String,
     /// Url to download
     pub url: String,
+    /// Main class and libraries, if the core isn't started with `java -jar`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<StoreLaunch>,
+    /// How the jar was compiled, if `url` is a build tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<Build>,
+    /// Base URL of the provider the item was resolved from, a mirror or the built-in one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+}
+
+/// Installed [Launch] of a core
+#[derive(Debug, Clone, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize)]
+pub struct StoreLaunch {
+    /// Class to start
+    pub main_class: Option<String>,
+    /// Extra JVM arguments, see [Launch::CORE_JAR]
+    pub jvm_args: Vec<String>,
+    /// Classpath, in order, or the files made by the installer
+    pub libraries: Vec<StoreLibrary>,
+    /// Arguments of the installer, the core file is the installer if they are set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub install_args: Vec<String>,
+}
+
+/// Installed library of a core
+#[derive(Debug, Clone, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize)]
+pub struct StoreLibrary {
+    /// Maven coordinates, or the path under [LIBRARIES_DIR] for files made by an installer
+    pub name: String,
+    /// File hash for validation
+    pub hash: HashType,
+    /// Path to file location
+    pub path: String,
+    /// Path to linked path
+    pub symbol_link: String,
+    /// Url to download, empty for files made by an installer
+    pub url: String,
+}
+
+/// Item ready to be deployed, see [stage_all]
+pub(crate) struct Prepared {
+    pub(crate) item: Item,
+    pub(crate) meta: DownloadMeta,
+    pub(crate) staged: Staged,
+}
+
+/// Files of an item, downloaded and verified but not deployed yet
+pub(crate) struct Staged {
+    file: StagedFile,
+    /// Name of the deployed link
+    file_name: String,
+    launch: Option<StagedLaunch>,
+}
+
+/// Jar of a staged item
+enum StagedFile {
+    Downloaded(Download),
+    /// Compiled into the shared store
+    Built {
+        path: String,
+        hash: HashType,
+    },
+}
+
+/// Libraries of a staged core
+enum StagedLaunch {
+    Libraries {
+        launch: Launch,
+        libraries: Vec<StagedLibrary>,
+    },
+    /// Made by the installer
+    Installer {
+        launch: Launch,
+        output: InstallerOutput,
+    },
+}
+
+/// Library to deploy, without a download if the store has it already
+struct StagedLibrary {
+    library: StoreLibrary,
+    download: Option<Download>,
+}
+
+/// Files made by an installer, deleted with the staging directory
+struct InstallerOutput {
+    staging: TempDir,
+    libraries: Vec<StoreLibrary>,
+}
+
+/// File downloaded into its own directory of [STORE_TEMP_DIR], deleted on drop
+struct Download {
+    dir: TempDir,
+    path: PathBuf,
+    name: String,
+}
+
+impl Staged {
+    /// Staged jar
+    pub(crate) fn path(&self) -> &Path {
+        match &self.file {
+            StagedFile::Downloaded(download) => &download.path,
+            StagedFile::Built { path, .. } => Path::new(path),
+        }
+    }
 }
 
 impl Store {
     /// Adds a new item to the store, downloading and setting up required files
-    pub async fn push(
-        &mut self,
-        item: &Item,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    pub async fn push(&mut self, item: &Item) -> Result<(), StoreError> {
         // Fetch meta
         let meta = DownloadMeta::fetch(item).await?;
 
-        // Get store location and prefix
-        let (end_location, prefix) = get_store_item_location(item);
+        self.install(item, meta).await
+    }
 
-        // Download file
-        let (saved_temp_path, file_name) =
-            download_file(&meta.download_link, &prefix, &meta.hash).await?;
+    /// Adds an already resolved item to the store, downloading and setting up required files
+    ///
+    /// Nothing is deployed if any step fails.
+    pub async fn install(&mut self, item: &Item, meta: DownloadMeta) -> Result<(), StoreError> {
+        let staged = stage(item, &meta).await?;
+        let mut transaction = Transaction::default();
+        match self.deploy(item, meta, &staged, &mut transaction).await {
+            Ok(()) => {
+                transaction.commit();
+                Ok(())
+            }
+            Err(e) => {
+                transaction.rollback().await;
+                Err(e)
+            }
+        }
+    }
 
-        // End path for item
-        let end_path = format!("{}/{}.jar", end_location, &file_name);
+    /// Installs new items all together and saves the store
+    ///
+    /// Items are resolved, downloaded and verified in the temp dir concurrently, before
+    /// anything is deployed. Deploying is serialized, an installed item of the same provider
+    /// is replaced. If any step fails, the deployed files and the store are left as they were.
+    /// Frozen items are skipped, returns the items that were installed.
+    pub async fn fill_new(&mut self, items: Vec<&Item>) -> Result<Vec<Provider>, StoreError> {
+        let items = items
+            .into_iter()
+            .filter(|item| !item.options.freeze || item.options.force_update)
+            .map(|item| (item.clone(), None))
+            .collect();
+        let prepared = stage_all(items).await?;
+        self.commit_prepared(&[], &prepared).await?;
+        Ok(prepared.into_iter().map(|x| x.item.provider).collect())
+    }
 
-        // Move file to final location
-        tokio::fs::rename(&saved_temp_path, &end_path).await?;
+    /// Removes the items of `removed` and deploys `prepared` in place of the installed
+    /// ones, then saves the store
+    ///
+    /// If any step fails, the deployed files and the store are left as they were.
+    pub(crate) async fn commit_prepared(
+        &mut self,
+        removed: &[Provider],
+        prepared: &[Prepared],
+    ) -> Result<(), StoreError> {
+        let backup = self.clone();
+        let mut transaction = Transaction::default();
+        let result = async {
+            let replaced = prepared.iter().map(|x| &x.item.provider);
+            for provider in removed.iter().chain(replaced) {
+                self.unlink(provider, &mut transaction).await?;
+            }
+            for x in prepared {
+                self.deploy(&x.item, x.meta.clone(), &x.staged, &mut transaction)
+                    .await?;
+            }
+            transaction.track(STORE_PATH).await?;
+            self.save(STORE_PATH).await
+        }
+        .await;
 
-        let sym_link_end = make_symbol_link(item, file_name, &end_path).await?;
+        match result {
+            Ok(()) => {
+                transaction.commit();
+                Ok(())
+            }
+            Err(e) => {
+                transaction.rollback().await;
+                *self = backup;
+                Err(e)
+            }
+        }
+    }
+
+    /// Removes the item of the given provider, its links are moved aside by the transaction
+    async fn unlink(
+        &mut self,
+        provider: &Provider,
+        transaction: &mut Transaction,
+    ) -> Result<(), StoreError> {
+        let Some(index) = self.inner.iter().position(|x| &x.item.provider == provider) else {
+            return Ok(());
+        };
+        let item = self.inner.remove(index);
+        let libraries = item.launch.iter().flat_map(|x| &x.libraries);
+        for link in std::iter::once(&item.symbol_link).chain(libraries.map(|x| &x.symbol_link)) {
+            transaction.track(link).await?;
+        }
+        Ok(())
+    }
+
+    /// Moves staged files into place and adds the item, every written path is tracked
+    async fn deploy(
+        &mut self,
+        item: &Item,
+        meta: DownloadMeta,
+        staged: &Staged,
+        transaction: &mut Transaction,
+    ) -> Result<(), StoreError> {
+        let (end_path, hash) = match &staged.file {
+            // Move file into the shared store, it is kept by its sha256 when there is no hash
+            StagedFile::Downloaded(download) => {
+                let (end_path, sha256) = SharedStore::global()
+                    .add(&download.path, &staged.file_name)
+                    .await?;
+                let hash = match meta.hash {
+                    HashType::None => sha256,
+                    hash => hash,
+                };
+                (end_path.to_string_lossy().to_string(), hash)
+            }
+            // Built jars are in the shared store already
+            StagedFile::Built { path, hash } => (path.clone(), hash.clone()),
+        };
+
+        let sym_link_end =
+            make_symbol_link(item, &staged.file_name, &end_path, transaction).await?;
+
+        let launch = match &staged.launch {
+            Some(StagedLaunch::Installer { launch, output }) => {
+                Some(deploy_installer(output, launch, transaction).await?)
+            }
+            Some(StagedLaunch::Libraries { launch, libraries }) => {
+                let mut installed = Vec::with_capacity(libraries.len());
+                for library in libraries {
+                    installed.push(deploy_library(library, transaction).await?);
+                }
+                Some(StoreLaunch {
+                    main_class: launch.main_class.clone(),
+                    jvm_args: launch.jvm_args.clone(),
+                    libraries: installed,
+                    install_args: launch.install_args.clone(),
+                })
+            }
+            None => None,
+        };
 
         // Push to store
         self.inner.push(StoreItem {
@@ -64,70 +297,91 @@ impl Store {
                 game_version: Some(meta.game_version),
                 version_build: meta.version_build,
                 channel: item.version.channel,
+                loader_version: meta.loader_version,
+                installer_version: meta.installer_version,
             }),
-            hash: meta.hash,
+            hash,
             path: end_path,
             url: meta.download_link,
             symbol_link: sym_link_end,
+            launch,
+            build: meta.build,
+            base: meta.base,
         });
 
         Ok(())
     }
 
-    /// Updates store with new items while maintaining a backup in case of failure
-    pub async fn fill_new(
-        &mut self,
-        items: Vec<&Item>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-        // Create backup of current store
-        let backup = Arc::new(Mutex::new(self.clone()));
-
-        // Try to update store
-        let handles = items
-            .into_iter()
-            .filter(|item| !item.options.freeze || item.options.force_update)
-            .cloned()
-            .map(|item| {
-                let backup_clone = backup.clone();
-                tokio::spawn(async move {
-                    let mut store = backup_clone.lock().await;
-                    store.push(&item).await
-                })
-            })
-            .collect::<Vec<_>>();
+    /// Loads store from file
+    pub async fn load(path: &str) -> Result<Self, StoreError> {
+        let bytes = tokio::fs::read(path).await.map_err(StoreError::io(path))?;
+        ron::de::from_bytes(&bytes).map_err(|source| StoreError::Parse {
+            path: path.into(),
+            source,
+        })
+    }
 
-        for handle in handles {
-            handle.await??;
+    /// Loads store from file, or returns an empty store if the file doesn't exist yet
+    pub async fn load_or_default(path: &str) -> Result<Self, StoreError> {
+        if tokio::fs::try_exists(path)
+            .await
+            .map_err(StoreError::io(path))?
+        {
+            Self::load(path).await
+        } else {
+            Ok(Self::default())
         }
+    }
 
-        // Save updated store
-        self.save(STORE_PATH).await?;
-
-        // add clear all symbol links if got error
-
-        *self = backup.lock().await.clone();
+    /// Removes the mod or plugin `name`, like `kind`, and deletes its symbolic link
+    ///
+    /// The downloaded file stays in the shared store until no instance uses it.
+    pub async fn remove_extension(
+        &mut self,
+        name: &str,
+        kind: &ExtensionType,
+    ) -> Result<Option<StoreItem>, StoreError> {
+        let Some(provider) = self
+            .inner
+            .iter()
+            .map(|x| &x.item.provider)
+            .find(|x| x.is_extension_named(name, kind))
+            .cloned()
+        else {
+            return Ok(None);
+        };
+        self.remove(&provider).await
+    }
 
-        Ok(())
+    /// Removes the item of the given provider and deletes its symbolic link
+    ///
+    /// The downloaded file stays in the shared store until no instance uses it.
+    pub async fn remove(&mut self, provider: &Provider) -> Result<Option<StoreItem>, StoreError> {
+        let Some(index) = self.inner.iter().position(|x| &x.item.provider == provider) else {
+            return Ok(None);
+        };
+        let item = self.inner.remove(index);
+        remove_symbol_link(&item)
+            .await
+            .map_err(StoreError::io(&item.symbol_link))?;
+        Ok(Some(item))
     }
 
-    /// Loads store from file
-    pub async fn load(
-        path: &str,
-    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync + 'static>> {
-        Ok(ron::de::from_bytes(&tokio::fs::read(path).await?)?)
+    /// Finds the store item installed for the given provider
+    pub fn find(&self, provider: &Provider) -> Option<&StoreItem> {
+        self.inner.iter().find(|x| &x.item.provider == provider)
     }
 
     /// Validates all store items, returning list of invalid ones
-    pub async fn validate(
-        &mut self,
-    ) -> Result<Vec<StoreItem>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    pub async fn validate(&mut self) -> Result<Vec<StoreItem>, StoreError> {
         let mut invalid_items = Vec::new();
 
         for item in &self.inner {
             let symbol_link_valid = check_symbol_link(item).await;
             let hash_valid = check_file_hash(item).await;
+            let libraries_valid = check_libraries(item).await;
 
-            if !symbol_link_valid || !hash_valid {
+            if !symbol_link_valid || !hash_valid || !libraries_valid {
                 invalid_items.push(item.clone());
             }
         }
@@ -136,71 +390,271 @@ impl Store {
     }
 
     /// Attempts to repair invalid items, returns list of items that couldn't be repaired
-    pub async fn repair(
-        &mut self,
-    ) -> Result<Vec<StoreItem>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    pub async fn repair(&mut self) -> Result<Vec<StoreItem>, StoreError> {
         // Get list of invalid items
         let invalid_items = self.validate().await?;
         let mut failed_repairs = Vec::new();
 
         for invalid_item in &invalid_items {
-            // Try to repair each invalid item
+            // Try to repair each invalid item, built jars and installer files may change
+            let mut transaction = Transaction::default();
             let repair_result = async {
-                // Check file
-                if !check_file_hash(invalid_item).await {
-                    // If file is corrupted or missing, download it again
-                    let (_, prefix) = get_store_item_location(&invalid_item.item);
-                    let (saved_temp_path, _) =
-                        download_file(&invalid_item.url, &prefix, &invalid_item.hash).await?;
-
-                    // Move file to destination
-                    tokio::fs::rename(&saved_temp_path, &invalid_item.path).await?;
+                let mut repaired = invalid_item.clone();
+
+                // Check file, a good copy kept on the host is restored first, it is there offline
+                if !check_file_hash(invalid_item).await && !restore_from_store(invalid_item).await?
+                {
+                    match &invalid_item.build {
+                        // Compiled jars without a good copy are built again
+                        Some(build) => {
+                            let (path, hash) = build_core(
+                                &invalid_item.item.provider,
+                                &invalid_item.url,
+                                build,
+                                true,
+                            )
+                            .await?;
+                            repaired.path = path;
+                            repaired.hash = hash;
+                        }
+                        // If file is corrupted or missing, download it again
+                        None => {
+                            let prefix = get_store_item_prefix(&invalid_item.item);
+                            let download = download_file(
+                                &invalid_item.item.provider,
+                                &invalid_item.url,
+                                &prefix,
+                                &invalid_item.hash,
+                            )
+                            .await?;
+
+                            // Move file into the shared store
+                            let (path, _) = SharedStore::global()
+                                .add(&download.path, &download.name)
+                                .await?;
+                            repaired.path = path.to_string_lossy().to_string();
+                        }
+                    }
                 }
 
-                // Check symbolic link
-                if !check_symbol_link(invalid_item).await {
+                // Check symbolic link, it is moved along with the file
+                if repaired.path != invalid_item.path || !check_symbol_link(invalid_item).await {
                     // If symbolic link is corrupted or missing, recreate it
-                    // First remove old link if exists
-                    // ignore error
-                    let _ = tokio::fs::remove_file(&invalid_item.symbol_link).await;
+                    // The old link is moved aside first
+                    transaction.track(&invalid_item.symbol_link).await?;
 
                     // Create new symbolic link
-                    tokio::fs::symlink_file(&invalid_item.path, &invalid_item.symbol_link).await?;
+                    symlink(&repaired.path, &invalid_item.symbol_link)
+                        .await
+                        .map_err(StoreError::io(&invalid_item.symbol_link))?;
+                }
+
+                // Download or relink broken libraries, files of an installer are made again
+                if let Some(launch) = &invalid_item.launch {
+                    if launch.install_args.is_empty() {
+                        for library in &launch.libraries {
+                            if !check_library(library).await {
+                                let staged = stage_library(
+                                    &invalid_item.item.provider,
+                                    Library::from(library),
+                                )
+                                .await?;
+                                deploy_library(&staged, &mut transaction).await?;
+                            }
+                        }
+                    } else if !check_libraries(invalid_item).await {
+                        let output =
+                            run_installer(Path::new(&repaired.path), &launch.install_args).await?;
+                        repaired.launch = Some(
+                            deploy_installer(&output, &Launch::from(launch), &mut transaction)
+                                .await?,
+                        );
+                    }
                 }
 
-                Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+                Ok::<StoreItem, StoreError>(repaired)
             }
             .await;
 
-            // If failed to repair item, add it to failed repairs list
-            if repair_result.is_err() {
-                failed_repairs.push(invalid_item.clone());
+            match repair_result {
+                Ok(repaired) => {
+                    transaction.commit();
+                    if let Some(item) = self
+                        .inner
+                        .iter_mut()
+                        .find(|x| x.item.provider == invalid_item.item.provider)
+                    {
+                        *item = repaired;
+                    }
+                }
+                // If failed to repair item, add it to failed repairs list
+                Err(_) => {
+                    transaction.rollback().await;
+                    failed_repairs.push(invalid_item.clone())
+                }
             }
         }
 
         Ok(failed_repairs)
     }
 
+    /// Sha256 of the serialized store, changes with any installed item
+    pub fn digest(&self) -> Result<String, StoreError> {
+        Ok(HashType::compute_sha256(ron::ser::to_string(self)?))
+    }
+
     /// Saves store to file
-    pub async fn save(
-        &self,
-        path: &str,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    pub async fn save(&self, path: &str) -> Result<(), StoreError> {
         tokio::fs::write(
             path,
             ron::ser::to_string_pretty(&self, PrettyConfig::default().enumerate_arrays(true))?,
         )
-        .await?;
+        .await
+        .map_err(StoreError::io(path))?;
         Ok(())
     }
 }
 
+/// Resolves the items without metadata and stages all of them at once, the client
+/// bounds the requests in flight
+///
+/// The first error stops the other tasks, what they staged is dropped.
+pub(crate) async fn stage_all(
+    items: Vec<(Item, Option<DownloadMeta>)>,
+) -> Result<Vec<Prepared>, StoreError> {
+    let mut tasks = JoinSet::new();
+    for (index, (item, meta)) in items.into_iter().enumerate() {
+        tasks.spawn(async move {
+            let meta = match meta {
+                Some(meta) => meta,
+                None => DownloadMeta::fetch(&item).await?,
+            };
+            let staged = stage(&item, &meta).await?;
+            Ok::<_, StoreError>((index, Prepared { item, meta, staged }))
+        });
+    }
+
+    let mut prepared = Vec::with_capacity(tasks.len());
+    while let Some(result) = tasks.join_next().await {
+        match result.map_err(StoreError::from).and_then(|x| x) {
+            Ok(x) => prepared.push(x),
+            Err(e) => {
+                // waits for the tasks to stop, the tools they run are killed
+                tasks.shutdown().await;
+                return Err(e);
+            }
+        }
+    }
+    prepared.sort_by_key(|(index, _)| *index);
+    Ok(prepared.into_iter().map(|(_, x)| x).collect())
+}
+
+/// Downloads or builds the files of an item into the temp dir and verifies them
+async fn stage(item: &Item, meta: &DownloadMeta) -> Result<Staged, StoreError> {
+    let (file, file_name) = match &meta.build {
+        // Compile it, or take the jar of the same revision
+        Some(build) => {
+            let (path, hash) = match &meta.hash {
+                // a locked jar, rebuilds aren't byte-identical
+                HashType::SHA256(sha256) => {
+                    let path =
+                        SharedStore::global()
+                            .find_sha256(sha256)
+                            .await
+                            .ok_or_else(|| StoreError::LockedBuildMissing {
+                                item: item.provider.describe(),
+                                name: build.name.clone(),
+                            })?;
+                    (path.to_string_lossy().to_string(), meta.hash.clone())
+                }
+                _ => build_core(&item.provider, &meta.download_link, build, false).await?,
+            };
+            (
+                StagedFile::Built { path, hash },
+                format!("{}.jar", build.name),
+            )
+        }
+        None => {
+            let prefix = get_store_item_prefix(item);
+            let download =
+                download_file(&item.provider, &meta.download_link, &prefix, &meta.hash).await?;
+            let file_name = download.name.clone();
+            (StagedFile::Downloaded(download), file_name)
+        }
+    };
+
+    let launch = match &meta.launch {
+        Some(launch) if !launch.install_args.is_empty() => {
+            let installer = match &file {
+                StagedFile::Downloaded(download) => download.path.clone(),
+                StagedFile::Built { path, .. } => PathBuf::from(path),
+            };
+            Some(StagedLaunch::Installer {
+                output: run_installer(&installer, &launch.install_args).await?,
+                launch: launch.clone(),
+            })
+        }
+        Some(launch) => {
+            let mut libraries = Vec::with_capacity(launch.libraries.len());
+            for library in &launch.libraries {
+                libraries.push(stage_library(&item.provider, library.clone()).await?);
+            }
+            Some(StagedLaunch::Libraries {
+                launch: launch.clone(),
+                libraries,
+            })
+        }
+        None => None,
+    };
+
+    Ok(Staged {
+        file,
+        file_name,
+        launch,
+    })
+}
+
+/// Creates the store layout and the extension directory used by the platform
+///
+/// The instance is registered in the shared store, so that its files are kept.
+pub async fn create_layout(platform: &Platform) -> Result<(), StoreError> {
+    let shared = SharedStore::global();
+    shared.create().await?;
+    shared.register(STORE_PATH).await?;
+
+    for dir in [
+        STORE_DIR,
+        STORE_EXTENSIONS_DIR,
+        STORE_TEMP_DIR,
+        STORE_CORES_DIR,
+        STORE_PLUGINS_DIR,
+        STORE_MODS_DIR,
+        STORE_LIBRARIES_DIR,
+    ] {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(StoreError::io(dir))?;
+    }
+    if platform.supports_plugins() {
+        tokio::fs::create_dir_all(PLUGINS_DIR)
+            .await
+            .map_err(StoreError::io(PLUGINS_DIR))?;
+    }
+    if platform.supports_mods() {
+        tokio::fs::create_dir_all(MODS_DIR)
+            .await
+            .map_err(StoreError::io(MODS_DIR))?;
+    }
+    Ok(())
+}
+
 /// Creates a symbolic link for the given item
 async fn make_symbol_link(
     item: &Item,
-    file_name: String,
-    end_path: &String,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    file_name: &str,
+    end_path: &str,
+    transaction: &mut Transaction,
+) -> Result<String, StoreError> {
     let sym_link_end = format!(
         "{}/{}",
         match &item.provider {
@@ -210,15 +664,362 @@ async fn make_symbol_link(
                 ExtensionType::Plugin(_) => PLUGINS_DIR,
             },
         },
-        &file_name
+        file_name
     );
-    tokio::fs::symlink_file(end_path, &sym_link_end).await?;
+    transaction.track(&sym_link_end).await?;
+    symlink(end_path, &sym_link_end)
+        .await
+        .map_err(StoreError::io(&sym_link_end))?;
     Ok(sym_link_end)
 }
 
+/// Creates a symbolic link at `link` pointing to the absolute path of `original`
+async fn symlink(original: &str, link: &str) -> std::io::Result<()> {
+    let original = tokio::fs::canonicalize(original).await?;
+    #[cfg(unix)]
+    {
+        tokio::fs::symlink(original, link).await
+    }
+    #[cfg(windows)]
+    {
+        tokio::fs::symlink_file(original, link).await
+    }
+}
+
+/// Removes the symbolic links of the item and its libraries, ignoring missing ones
+async fn remove_symbol_link(item: &StoreItem) -> std::io::Result<()> {
+    let libraries = item.launch.iter().flat_map(|x| &x.libraries);
+    for link in std::iter::once(&item.symbol_link).chain(libraries.map(|x| &x.symbol_link)) {
+        match tokio::fs::remove_file(link).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Downloads a library into the temp dir, unless it is in the store already,
+/// `source` is the provider of the core
+async fn stage_library(source: &Provider, library: Library) -> Result<StagedLibrary, StoreError> {
+    let maven_path = Library::path(&library.name).ok_or_else(|| StoreError::InvalidUrl {
+        url: library.url.clone(),
+    })?;
+    let installed = StoreLibrary {
+        path: format!("{}/{}", STORE_LIBRARIES_DIR, maven_path),
+        symbol_link: format!("{}/{}", LIBRARIES_DIR, maven_path),
+        name: library.name,
+        hash: library.hash,
+        url: library.url,
+    };
+
+    let download = match check_hash(&installed.path, &installed.hash).await {
+        true => None,
+        false => Some(download_file(source, &installed.url, "library-", &installed.hash).await?),
+    };
+    Ok(StagedLibrary {
+        library: installed,
+        download,
+    })
+}
+
+/// Moves a staged library into the store and links it into [LIBRARIES_DIR]
+async fn deploy_library(
+    staged: &StagedLibrary,
+    transaction: &mut Transaction,
+) -> Result<StoreLibrary, StoreError> {
+    let library = &staged.library;
+    if let Some(download) = &staged.download {
+        create_parent(&library.path).await?;
+        transaction.track(&library.path).await?;
+        tokio::fs::rename(&download.path, &library.path)
+            .await
+            .map_err(StoreError::io(&library.path))?;
+    }
+
+    link_library(library, transaction).await?;
+    Ok(library.clone())
+}
+
+/// Links a library from the store into [LIBRARIES_DIR], replacing an existing file
+async fn link_library(
+    library: &StoreLibrary,
+    transaction: &mut Transaction,
+) -> Result<(), StoreError> {
+    create_parent(&library.symbol_link).await?;
+    transaction.track(&library.symbol_link).await?;
+    symlink(&library.path, &library.symbol_link)
+        .await
+        .map_err(StoreError::io(&library.symbol_link))
+}
+
+/// Runs an installer jar in a temporary directory, its files are deployed by
+/// [deploy_installer]
+///
+/// The installer output goes to [STORE_LOGS_DIR].
+async fn run_installer(
+    installer: &Path,
+    install_args: &[String],
+) -> Result<InstallerOutput, StoreError> {
+    let staging = Builder::new()
+        .prefix("installer-")
+        .tempdir_in(STORE_TEMP_DIR)
+        .map_err(StoreError::io(STORE_TEMP_DIR))?;
+    let installer_path = tokio::fs::canonicalize(installer)
+        .await
+        .map_err(StoreError::io(installer))?;
+
+    let log_path = PathBuf::from(STORE_LOGS_DIR).join(format!(
+        "{}.log",
+        installer_path
+            .file_name()
+            .map(|x| x.to_string_lossy())
+            .unwrap_or_default()
+    ));
+    run_tool(&installer_path, install_args, staging.path(), log_path).await?;
+
+    let made_libraries = staging.path().join("libraries");
+    let mut libraries = Vec::new();
+    for name in list_files(&made_libraries).await? {
+        let made = made_libraries.join(&name);
+        let bytes = tokio::fs::read(&made)
+            .await
+            .map_err(StoreError::io(&made))?;
+        libraries.push(StoreLibrary {
+            hash: HashType::new_sha256(HashType::compute_sha256(bytes)),
+            path: format!("{}/{}", STORE_LIBRARIES_DIR, name),
+            symbol_link: format!("{}/{}", LIBRARIES_DIR, name),
+            url: String::new(),
+            name,
+        });
+    }
+
+    Ok(InstallerOutput { staging, libraries })
+}
+
+/// Copies the launch scripts of an installer to [CORE_DIR] and moves the libraries it
+/// made into the store
+async fn deploy_installer(
+    output: &InstallerOutput,
+    launch: &Launch,
+    transaction: &mut Transaction,
+) -> Result<StoreLaunch, StoreError> {
+    // user_jvm_args.txt is written from the config
+    for script in ["run.sh", "run.bat"] {
+        let made = output.staging.path().join(script);
+        if tokio::fs::try_exists(&made).await.unwrap_or(false) {
+            let target = format!("{}/{}", CORE_DIR, script);
+            transaction.track(&target).await?;
+            tokio::fs::copy(&made, &target)
+                .await
+                .map_err(StoreError::io(&target))?;
+        }
+    }
+
+    let made_libraries = output.staging.path().join("libraries");
+    for library in &output.libraries {
+        if !check_hash(&library.path, &library.hash).await {
+            create_parent(&library.path).await?;
+            transaction.track(&library.path).await?;
+            tokio::fs::rename(made_libraries.join(&library.name), &library.path)
+                .await
+                .map_err(StoreError::io(&library.path))?;
+        }
+        link_library(library, transaction).await?;
+    }
+
+    Ok(StoreLaunch {
+        main_class: launch.main_class.clone(),
+        jvm_args: launch.jvm_args.clone(),
+        libraries: output.libraries.clone(),
+        install_args: launch.install_args.clone(),
+    })
+}
+
+/// Compiles a core with the build tool at `url` in a temporary directory
+///
+/// The jar is kept as `<sha256>-<name>.jar` in the shared store, it isn't built again for
+/// the same revision unless `rebuild` is set. Returns the path and sha256 of the jar.
+async fn build_core(
+    source: &Provider,
+    url: &str,
+    build: &Build,
+    rebuild: bool,
+) -> Result<(String, HashType), StoreError> {
+    let shared = SharedStore::global();
+    let name = format!("{}.jar", build.name);
+    if !rebuild {
+        if let Some(path) = shared.find(&name).await {
+            if let Ok(bytes) = tokio::fs::read(&path).await {
+                return Ok((
+                    path.to_string_lossy().to_string(),
+                    HashType::new_sha256(HashType::compute_sha256(bytes)),
+                ));
+            }
+        }
+    }
+
+    // the download directory is the work directory of the tool
+    let tool = download_file(source, url, "build-", &HashType::None).await?;
+    let work_dir = tool.dir.path().to_path_buf();
+    let output_dir = work_dir.join("output");
+    let result = async {
+        let output = tokio::fs::canonicalize(&work_dir)
+            .await
+            .map_err(StoreError::io(&work_dir))?
+            .join("output");
+        let args = build
+            .args
+            .iter()
+            .map(|x| x.replace(Build::OUTPUT_DIR, &output.to_string_lossy()))
+            .collect::<Vec<_>>();
+        let log_path = PathBuf::from(STORE_LOGS_DIR).join(format!("{}.log", build.name));
+        run_tool(&tool.path, &args, &work_dir, log_path.clone()).await?;
+
+        let built = list_files(&output_dir)
+            .await?
+            .into_iter()
+            .find(|x| x.ends_with(".jar"))
+            .ok_or_else(|| StoreError::NoOutput {
+                tool: url.to_string(),
+                log: log_path,
+            })?;
+        let (end_path, hash) = shared.add(&output_dir.join(built), &name).await?;
+        Ok((end_path.to_string_lossy().to_string(), hash))
+    }
+    .await;
+
+    // work directory holds the sources, drop it either way
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+    result
+}
+
+/// Runs `java -jar <jar> <args>` in `dir`, the output goes to `log_path`
+async fn run_tool(
+    jar: &Path,
+    args: &[String],
+    dir: &Path,
+    log_path: PathBuf,
+) -> Result<(), StoreError> {
+    tokio::fs::create_dir_all(STORE_LOGS_DIR)
+        .await
+        .map_err(StoreError::io(STORE_LOGS_DIR))?;
+    let log = std::fs::File::create(&log_path).map_err(StoreError::io(&log_path))?;
+    let log_err = log.try_clone().map_err(StoreError::io(&log_path))?;
+
+    let jar = tokio::fs::canonicalize(jar)
+        .await
+        .map_err(StoreError::io(jar))?;
+    let status = tokio::process::Command::new("java")
+        .arg("-jar")
+        .arg(&jar)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(log_err)
+        // a cancelled install doesn't leave the tool writing to its staging directory
+        .kill_on_drop(true)
+        .status()
+        .await
+        .map_err(StoreError::io("java"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(StoreError::Tool {
+            tool: jar.to_string_lossy().to_string(),
+            status,
+            log: log_path,
+        })
+    }
+}
+
+/// Regular files under `dir`, relative to it with `/` separators, sorted
+async fn list_files(dir: &Path) -> Result<Vec<String>, StoreError> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        let mut entries = match tokio::fs::read_dir(&current).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(StoreError::io(&current)(e)),
+        };
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(StoreError::io(&current))?
+        {
+            let path = entry.path();
+            let file_type = entry.file_type().await.map_err(StoreError::io(&path))?;
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_file() {
+                if let Ok(relative) = path.strip_prefix(dir) {
+                    let parts = relative
+                        .components()
+                        .map(|x| x.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>();
+                    files.push(parts.join("/"));
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Puts back the file of the item from a good copy, see [SharedStore::copies]
+///
+/// The copy is written next to the item file and renamed over it. Returns `false` if no
+/// copy has the hash of the item.
+async fn restore_from_store(item: &StoreItem) -> Result<bool, StoreError> {
+    let file_name = Path::new(&item.path)
+        .file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let sha256 = match &item.hash {
+        HashType::SHA256(sha256) => Some(sha256.as_str()),
+        _ => None,
+    };
+
+    for copy in SharedStore::global().copies(&file_name, sha256).await {
+        let same = match (
+            tokio::fs::canonicalize(&copy).await,
+            tokio::fs::canonicalize(&item.path).await,
+        ) {
+            (Ok(copy), Ok(path)) => copy == path,
+            _ => copy == Path::new(&item.path),
+        };
+        if same || !check_hash(&copy.to_string_lossy(), &item.hash).await {
+            continue;
+        }
+
+        // a copy and not a link, the file mustn't share the fate of the one it came from
+        create_parent(&item.path).await?;
+        let temp = format!("{}.{}.tmp", item.path, std::process::id());
+        tokio::fs::copy(&copy, &temp)
+            .await
+            .map_err(StoreError::io(&temp))?;
+        tokio::fs::rename(&temp, &item.path)
+            .await
+            .map_err(StoreError::io(&item.path))?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Creates the parent directory of a file
+async fn create_parent(path: &str) -> Result<(), StoreError> {
+    match std::path::Path::new(path).parent() {
+        Some(parent) => tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(StoreError::io(parent)),
+        None => Ok(()),
+    }
+}
+
 /// Checks if a symbolic link is valid and exists
 pub async fn check_symbol_link(item: &StoreItem) -> bool {
-    if let Ok(metadata) = tokio::fs::symlink_metadata(&item.path).await {
+    if let Ok(metadata) = tokio::fs::symlink_metadata(&item.symbol_link).await {
         metadata.is_symlink()
     } else {
         false
@@ -227,52 +1028,124 @@ pub async fn check_symbol_link(item: &StoreItem) -> bool {
 
 /// Validates file integrity by comparing its hash
 pub async fn check_file_hash(item: &StoreItem) -> bool {
-    if let Ok(file) = tokio::fs::read(&item.path).await.as_deref() {
-        item.hash.compare(file).is_ok()
-    } else {
-        false
+    check_hash(&item.path, &item.hash).await
+}
+
+/// Checks the file and the symbolic link of a library
+pub async fn check_library(library: &StoreLibrary) -> bool {
+    let linked = tokio::fs::symlink_metadata(&library.symbol_link)
+        .await
+        .is_ok_and(|x| x.is_symlink());
+    linked && check_hash(&library.path, &library.hash).await
+}
+
+/// Checks every library of the item
+async fn check_libraries(item: &StoreItem) -> bool {
+    for library in item.launch.iter().flat_map(|x| &x.libraries) {
+        if !check_library(library).await {
+            return false;
+        }
+    }
+    true
+}
+
+async fn check_hash(path: &str, hash: &HashType) -> bool {
+    match tokio::fs::read(path).await {
+        Ok(file) => hash.compare(file).is_ok(),
+        Err(_) => false,
     }
 }
 
-/// Gets the storage location and prefix for an item based on its provider
-fn get_store_item_location(item: &Item) -> (&'static str, String) {
+/// Gets the download prefix for an item based on its provider
+fn get_store_item_prefix(item: &Item) -> String {
     match &item.provider {
-        Provider::Core(platform) => (STORE_CORES_DIR, format!("{}-{}-", &item.provider, platform)),
-        Provider::Extension((_, _, ext)) => (
-            match ext {
-                ExtensionType::Mod(_) => STORE_MODS_DIR,
-                ExtensionType::Plugin(_) => STORE_PLUGINS_DIR,
-            },
-            format!("{}-{}-", &item.provider, ext),
-        ),
+        Provider::Core(platform) => format!("{}-{}-", &item.provider, platform),
+        Provider::Extension((_, _, ext)) => format!("{}-{}-", &item.provider, ext),
     }
 }
 
-/// Downloads a file from URL and validates its hash
+/// Downloads a file with the provider of `source` to a temp file and validates its hash
 async fn download_file(
+    source: &Provider,
     url: &str,
     prefix: &str,
     expected_hash: &HashType,
-) -> Result<(PathBuf, String), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let response = reqwest::get(url).await?;
+) -> Result<Download, StoreError> {
+    let download_error = |source| StoreError::Download {
+        url: url.to_string(),
+        source,
+    };
+    // the slot is held until the body is read
+    let client = Client::global()?;
+    let _permit = client.limiter().acquire(url).await;
+    let mut response = Registry::global().download(source, url).await?;
 
-    let temp_dir = Builder::new().prefix(prefix).tempdir_in(STORE_TEMP_DIR)?;
+    // the caller moves the file out of it, the directory is deleted with the download
+    let temp_dir = Builder::new()
+        .prefix(prefix)
+        .tempdir_in(STORE_TEMP_DIR)
+        .map_err(StoreError::io(STORE_TEMP_DIR))?;
 
     let file_name = response
         .url()
         .path_segments()
-        .and_then(|segments| segments.last())
+        .and_then(|mut segments| segments.next_back())
         .and_then(|name| if name.is_empty() { None } else { Some(name) })
-        .ok_or_else(|| format!("Invalid path in URL: {:#?}", response.url()))?
+        .ok_or_else(|| StoreError::InvalidUrl {
+            url: response.url().to_string(),
+        })?
         .to_string();
 
-    let body = response.bytes().await?;
-    expected_hash.compare(&body)?;
-
     let file_path = temp_dir.path().join(&file_name);
-    let mut file = File::create_new(&file_path).await?;
+    let mut file = File::create_new(&file_path)
+        .await
+        .map_err(StoreError::io(&file_path))?;
 
-    file.write_all(&body).await?;
+    // written as it arrives, the hash is checked once the whole body is on disk
+    let mut hasher = expected_hash.hasher();
+    let mut transfer = Transfer::start(
+        source.describe(),
+        url.to_string(),
+        response.content_length(),
+    );
+    while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(StoreError::io(&file_path))?;
+        transfer.advance(chunk.len() as u64);
+    }
+    file.flush().await.map_err(StoreError::io(&file_path))?;
+    hasher.finish().map_err(|source| StoreError::Hash {
+        url: url.to_string(),
+        source,
+    })?;
+    transfer.finish();
+
+    Ok(Download {
+        dir: temp_dir,
+        path: file_path,
+        name: file_name,
+    })
+}
 
-    Ok((file_path, file_name))
+impl From<&StoreLibrary> for Library {
+    fn from(value: &StoreLibrary) -> Self {
+        Self {
+            name: value.name.clone(),
+            url: value.url.clone(),
+            hash: value.hash.clone(),
+        }
+    }
+}
+
+impl From<&StoreLaunch> for Launch {
+    fn from(value: &StoreLaunch) -> Self {
+        Self {
+            main_class: value.main_class.clone(),
+            jvm_args: value.jvm_args.clone(),
+            libraries: value.libraries.iter().map(Library::from).collect(),
+            install_args: value.install_args.clone(),
+        }
+    }
 }
@@ -0,0 +1,71 @@
+//! Filesystem changes of an install that are kept or undone together.
+use std::path::{Path, PathBuf};
+
+use prisma_core::STORE_TEMP_DIR;
+use tempfile::{Builder, TempDir};
+
+use crate::StoreError;
+
+/// Paths written by an install
+///
+/// Every path is tracked before it is written: a new path is deleted on rollback, an
+/// existing one is moved aside and put back. Files added to the shared store aren't
+/// tracked, they are content-addressed and unused ones are collected later.
+#[derive(Debug, Default)]
+pub(crate) struct Transaction {
+    /// Paths that didn't exist
+    created: Vec<PathBuf>,
+    /// Paths that existed, with the place they were moved to
+    replaced: Vec<(PathBuf, PathBuf)>,
+    /// Directory of the moved paths, deleted on commit
+    backup: Option<TempDir>,
+}
+
+impl Transaction {
+    /// Moves what is at `path` aside, so that it can be written
+    pub(crate) async fn track(&mut self, path: impl AsRef<Path>) -> Result<(), StoreError> {
+        let path = path.as_ref().to_path_buf();
+        if self.created.contains(&path) || self.replaced.iter().any(|(x, _)| x == &path) {
+            // written twice, the state before the first write is restored
+            let _ = tokio::fs::remove_file(&path).await;
+            return Ok(());
+        }
+
+        match tokio::fs::symlink_metadata(&path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => self.created.push(path),
+            Err(e) => return Err(StoreError::io(&path)(e)),
+            Ok(_) => {
+                let backup = match &self.backup {
+                    Some(backup) => backup.path().to_path_buf(),
+                    None => {
+                        let backup = Builder::new()
+                            .prefix("backup-")
+                            .tempdir_in(STORE_TEMP_DIR)
+                            .map_err(StoreError::io(STORE_TEMP_DIR))?;
+                        self.backup.insert(backup).path().to_path_buf()
+                    }
+                };
+                let moved = backup.join(self.replaced.len().to_string());
+                tokio::fs::rename(&path, &moved)
+                    .await
+                    .map_err(StoreError::io(&path))?;
+                self.replaced.push((path, moved));
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps every change
+    pub(crate) fn commit(self) {}
+
+    /// Undoes every change, newest first
+    pub(crate) async fn rollback(self) {
+        for path in self.created.iter().rev() {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        for (path, moved) in self.replaced.iter().rev() {
+            let _ = tokio::fs::remove_file(path).await;
+            let _ = tokio::fs::rename(moved, path).await;
+        }
+    }
+}
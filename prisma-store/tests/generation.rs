@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod generation {
+    use std::path::Path;
+
+    use prisma_core::{item::Item, platform::Platform};
+    use prisma_hash::HashType;
+    use prisma_store::{
+        generation::Generations,
+        lock::Lock,
+        shared::SharedStore,
+        store::{Store, StoreItem},
+    };
+
+    fn store(root: &Path, name: &str) -> Store {
+        Store {
+            inner: vec![StoreItem {
+                item: Item::new_plugin(name.to_string(), Platform::Paper, Default::default()),
+                hash: HashType::None,
+                path: root
+                    .join(format!("files/{}.jar", name))
+                    .to_string_lossy()
+                    .to_string(),
+                symbol_link: root
+                    .join(format!("server/plugins/{}.jar", name))
+                    .to_string_lossy()
+                    .to_string(),
+                url: format!("https://example.com/{}.jar", name),
+                launch: None,
+                build: None,
+                base: None,
+            }],
+        }
+    }
+
+    async fn read(path: impl AsRef<Path>) -> Option<String> {
+        tokio::fs::read_to_string(path).await.ok()
+    }
+
+    #[tokio::test]
+    async fn commit_and_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        SharedStore::set_global(SharedStore::new(root.join("shared")));
+        tokio::fs::create_dir_all(root.join("files")).await.unwrap();
+        for name in ["first", "second"] {
+            tokio::fs::write(root.join(format!("files/{}.jar", name)), name)
+                .await
+                .unwrap();
+        }
+        let generations = Generations::new(root.join("generations"), root.join("current"));
+        let first = root.join("server/plugins/first.jar");
+        let second = root.join("server/plugins/second.jar");
+
+        let made = generations
+            .commit(&store(root, "first"), &Lock::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(made.map(|x| x.number), Some(1));
+        assert_eq!(read(&first).await.as_deref(), Some("first"));
+        // nothing changed, no generation
+        let made = generations
+            .commit(&store(root, "first"), &Lock::default(), 0)
+            .await
+            .unwrap();
+        assert!(made.is_none());
+
+        generations
+            .commit(&store(root, "second"), &Lock::default(), 0)
+            .await
+            .unwrap();
+        assert_eq!(generations.current().await, Some(2));
+        assert_eq!(read(&first).await, None);
+        assert_eq!(read(&second).await.as_deref(), Some("second"));
+
+        let store_path = root.join("store.ron").to_string_lossy().to_string();
+        let lock_path = root.join("prisma.lock").to_string_lossy().to_string();
+        assert_eq!(generations.previous().await.unwrap(), Some(1));
+        generations
+            .rollback(1, &store_path, &lock_path)
+            .await
+            .unwrap();
+        assert_eq!(generations.current().await, Some(1));
+        assert_eq!(read(&first).await.as_deref(), Some("first"));
+        assert_eq!(read(&second).await, None);
+        assert_eq!(
+            Store::load(&store_path).await.unwrap(),
+            store(root, "first")
+        );
+
+        // the active generation is kept
+        generations
+            .commit(&store(root, "second"), &Lock::default(), 1)
+            .await
+            .unwrap();
+        let numbers = generations.list().await.unwrap();
+        assert_eq!(numbers.iter().map(|x| x.number).collect::<Vec<_>>(), [3]);
+    }
+}
@@ -0,0 +1,377 @@
+#[cfg(test)]
+mod install {
+    use std::sync::Arc;
+
+    use prisma_config::network::NetworkOptions;
+    use prisma_core::{
+        extension::ExtensionProvider, item::Item, options::Options, platform::Platform, provider,
+        STORE_PATH, STORE_TEMP_DIR,
+    };
+    use prisma_hash::HashType;
+    use prisma_providers::{Build, Client, DownloadMeta, Provider, ProviderError, Registry};
+    use prisma_store::{
+        lock::Lock,
+        plan::{Action, Change, Plan, Resolved},
+        progress::{subscribe, Progress},
+        shared::SharedStore,
+        store::{create_layout, Store},
+        StoreError,
+    };
+    use tempfile::TempDir;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::{Mutex, MutexGuard},
+    };
+
+    /// Installs use the process directory and globals, the tests take turns
+    static SERIAL: Mutex<()> = Mutex::const_new(());
+
+    /// Instance in its own directory, the current one until dropped
+    struct Instance {
+        _serial: MutexGuard<'static, ()>,
+        _dir: TempDir,
+    }
+
+    async fn instance(platform: Platform) -> Instance {
+        let serial = SERIAL.lock().await;
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+        SharedStore::set_global(SharedStore::new(dir.path().join("shared")));
+        Client::set_global(Client::new(&NetworkOptions::default()).unwrap());
+        Registry::set_global(
+            Registry::empty().with_extension("fixture", Arc::new(Fixture(serve().await))),
+        );
+        create_layout(&platform).await.unwrap();
+        Instance {
+            _serial: serial,
+            _dir: dir,
+        }
+    }
+
+    /// Serves `/<name>.jar` with `<name>` as the body
+    struct Fixture(String);
+
+    #[prisma_providers::async_trait]
+    impl Provider for Fixture {
+        fn name(&self) -> &str {
+            "fixture"
+        }
+
+        async fn resolve(&self, item: &Item) -> Result<DownloadMeta, ProviderError> {
+            let name = match &item.provider {
+                provider::Provider::Extension((name, _, _)) => name.clone(),
+                provider::Provider::Core(platform) => platform.to_string(),
+            };
+            Ok(DownloadMeta {
+                download_link: format!("{}/{}.jar", self.0, name),
+                hash: HashType::new_sha256(HashType::compute_sha256(&name)),
+                game_version: "1.21".to_string(),
+                version_build: None,
+                loader_version: None,
+                installer_version: None,
+                launch: None,
+                build: None,
+                base: None,
+            })
+        }
+
+        async fn versions(&self, _item: &Item) -> Result<Vec<String>, ProviderError> {
+            Ok(vec!["1.21".to_string()])
+        }
+    }
+
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let read = stream.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let body = path.trim_start_matches('/').trim_end_matches(".jar");
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    #[tokio::test]
+    async fn fill_new_rolls_back_on_failure() {
+        let _instance = instance(Platform::Paper).await;
+        Store::default().save(STORE_PATH).await.unwrap();
+        let saved = tokio::fs::read(STORE_PATH).await.unwrap();
+
+        let provider = || ExtensionProvider::Custom("fixture".to_string());
+        let plugin = Item::new_plugin("good".to_string(), Platform::Paper, provider());
+        // Paper has no mods directory, the second link can't be made
+        let broken = Item::new_mod("broken".to_string(), Platform::Paper, provider());
+
+        let mut store = Store::default();
+        assert!(matches!(
+            store.fill_new(vec![&plugin, &broken]).await,
+            Err(StoreError::Io { .. })
+        ));
+        assert!(store.inner.is_empty());
+        assert!(tokio::fs::symlink_metadata("./plugins/good.jar")
+            .await
+            .is_err());
+        assert_eq!(tokio::fs::read(STORE_PATH).await.unwrap(), saved);
+        let mut temp = tokio::fs::read_dir(STORE_TEMP_DIR).await.unwrap();
+        assert!(temp.next_entry().await.unwrap().is_none());
+
+        // frozen items aren't installed
+        let frozen = Item::new_plugin("frozen".to_string(), Platform::Paper, provider())
+            .with_options(Options {
+                freeze: true,
+                force_update: false,
+            });
+        let mut progress = subscribe();
+        let installed = store.fill_new(vec![&plugin, &frozen]).await.unwrap();
+        assert_eq!(installed, std::slice::from_ref(&plugin.provider));
+        assert!(store.find(&frozen.provider).is_none());
+        let mut events = Vec::new();
+        while let Ok(event) = progress.try_recv() {
+            events.push(event);
+        }
+        assert!(matches!(
+            events.first(),
+            Some(Progress::Started { url, total: Some(4), .. }) if url.ends_with("/good.jar")
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(Progress::Finished { bytes: 4, .. })
+        ));
+        assert_eq!(
+            tokio::fs::read_to_string("./plugins/good.jar")
+                .await
+                .unwrap(),
+            "good"
+        );
+        assert_eq!(Store::load(STORE_PATH).await.unwrap(), store);
+    }
+
+    #[tokio::test]
+    async fn apply_rolls_back_on_failure() {
+        let _instance = instance(Platform::Paper).await;
+        let provider = || ExtensionProvider::Custom("fixture".to_string());
+        let plugin = Item::new_plugin("good".to_string(), Platform::Paper, provider());
+        let other = Item::new_plugin("other".to_string(), Platform::Paper, provider());
+        // Paper has no mods directory, the link can't be made
+        let broken = Item::new_mod("broken".to_string(), Platform::Paper, provider());
+        let mut store = Store::default();
+        store.fill_new(vec![&plugin]).await.unwrap();
+        let saved = tokio::fs::read(STORE_PATH).await.unwrap();
+
+        let add = |item: &Item, meta: DownloadMeta| Change {
+            action: Action::Add,
+            item: item.clone(),
+            old: None,
+            new: Some(meta.into()),
+        };
+        let plan = Plan {
+            changes: vec![
+                Change {
+                    action: Action::Remove,
+                    item: plugin.clone(),
+                    old: store.find(&plugin.provider).map(Resolved::from),
+                    new: None,
+                },
+                add(&other, DownloadMeta::fetch(&other).await.unwrap()),
+                add(&broken, DownloadMeta::fetch(&broken).await.unwrap()),
+            ],
+            store: store.digest().unwrap(),
+        };
+        assert!(matches!(
+            store.apply(&plan).await,
+            Err(StoreError::Io { .. })
+        ));
+        assert_eq!(
+            tokio::fs::read_to_string("./plugins/good.jar")
+                .await
+                .unwrap(),
+            "good"
+        );
+        assert!(tokio::fs::symlink_metadata("./plugins/other.jar")
+            .await
+            .is_err());
+        assert_eq!(tokio::fs::read(STORE_PATH).await.unwrap(), saved);
+        assert_eq!(Store::load(STORE_PATH).await.unwrap(), store);
+    }
+
+    #[tokio::test]
+    async fn apply_rejects_store_changed_outside_the_plan() {
+        let _instance = instance(Platform::Paper).await;
+        let provider = || ExtensionProvider::Custom("fixture".to_string());
+        let plugin = Item::new_plugin("good".to_string(), Platform::Paper, provider());
+        let other = Item::new_plugin("other".to_string(), Platform::Paper, provider());
+        let mut store = Store::default();
+        let plan = Plan {
+            changes: vec![Change {
+                action: Action::Add,
+                item: other.clone(),
+                old: None,
+                new: Some(DownloadMeta::fetch(&other).await.unwrap().into()),
+            }],
+            store: store.digest().unwrap(),
+        };
+
+        // installed after the plan was built, the plan doesn't list it
+        store.fill_new(vec![&plugin]).await.unwrap();
+        assert!(matches!(
+            store.apply(&plan).await,
+            Err(StoreError::PlanOutdated { item }) if item == plugin.provider.describe()
+        ));
+        assert!(store.find(&other.provider).is_none());
+    }
+
+    #[tokio::test]
+    async fn install_locked_checks_hashes_before_deploying() {
+        let _instance = instance(Platform::Paper).await;
+        let provider = ExtensionProvider::Custom("fixture".to_string());
+        let plugin = Item::new_plugin("good".to_string(), Platform::Paper, provider);
+        let mut store = Store::default();
+        store.fill_new(vec![&plugin]).await.unwrap();
+        let saved = tokio::fs::read(STORE_PATH).await.unwrap();
+        let items = [plugin.clone()];
+        let installed = Lock::from_store(&store, &items).await.unwrap();
+
+        // another build, the download passes its first hash but not the second one
+        let mut lock = installed.clone();
+        lock.entries[0].version.version_build = Some("2".to_string());
+        lock.entries[0]
+            .hashes
+            .push(HashType::new_sha1(HashType::compute_sha1("tampered")));
+        assert!(matches!(
+            store.install_locked(&items, &lock).await,
+            Err(StoreError::LockMismatch { .. })
+        ));
+        assert!(
+            installed.entries[0]
+                .matches(store.find(&plugin.provider).unwrap())
+                .await
+        );
+        assert_eq!(tokio::fs::read(STORE_PATH).await.unwrap(), saved);
+
+        // a locked build is never compiled again
+        let mut lock = installed.clone();
+        lock.entries[0].version.version_build = Some("2".to_string());
+        lock.entries[0].build = Some(Build {
+            name: "good-2".to_string(),
+            args: vec![],
+        });
+        lock.entries[0].hashes = vec![HashType::new_sha256(HashType::compute_sha256("other"))];
+        assert!(matches!(
+            store.install_locked(&items, &lock).await,
+            Err(StoreError::LockedBuildMissing { .. })
+        ));
+        assert_eq!(tokio::fs::read(STORE_PATH).await.unwrap(), saved);
+    }
+
+    #[tokio::test]
+    async fn repairs_offline_from_shared_store() {
+        let _instance = instance(Platform::Paper).await;
+        let provider = ExtensionProvider::Custom("fixture".to_string());
+        let plugin = Item::new_plugin("good".to_string(), Platform::Paper, provider);
+        let mut store = Store::default();
+        store.fill_new(vec![&plugin]).await.unwrap();
+
+        // recorded before the shared store, the jar is only in the shared store
+        store.inner[0].path = "./.prisma/extensions/plugins/good.jar".to_string();
+        tokio::fs::remove_file("./plugins/good.jar").await.unwrap();
+
+        Client::set_global(
+            Client::new(&NetworkOptions::default())
+                .unwrap()
+                .with_offline(true),
+        );
+        assert!(store.repair().await.unwrap().is_empty());
+        assert_eq!(
+            tokio::fs::read_to_string("./plugins/good.jar")
+                .await
+                .unwrap(),
+            "good"
+        );
+    }
+
+    #[tokio::test]
+    async fn repairs_built_item_without_rebuilding() {
+        let _instance = instance(Platform::Paper).await;
+        let provider = ExtensionProvider::Custom("fixture".to_string());
+        let plugin = Item::new_plugin("good".to_string(), Platform::Paper, provider);
+        let mut store = Store::default();
+        store.fill_new(vec![&plugin]).await.unwrap();
+
+        // the jar was built, its tool can't be downloaded offline
+        store.inner[0].build = Some(Build {
+            name: "good".to_string(),
+            args: vec![],
+        });
+        store.inner[0].hash = HashType::new_sha256(HashType::compute_sha256("good"));
+        store.inner[0].path = "./.prisma/extensions/plugins/good.jar".to_string();
+        tokio::fs::remove_file("./plugins/good.jar").await.unwrap();
+
+        Client::set_global(
+            Client::new(&NetworkOptions::default())
+                .unwrap()
+                .with_offline(true),
+        );
+        assert!(store.repair().await.unwrap().is_empty());
+        assert_eq!(
+            tokio::fs::read_to_string("./plugins/good.jar")
+                .await
+                .unwrap(),
+            "good"
+        );
+    }
+
+    #[tokio::test]
+    async fn repairs_offline_from_registered_instance() {
+        let instance = instance(Platform::Paper).await;
+        let provider = ExtensionProvider::Custom("fixture".to_string());
+        let plugin = Item::new_plugin("good".to_string(), Platform::Paper, provider);
+        let mut store = Store::default();
+        store.fill_new(vec![&plugin]).await.unwrap();
+
+        // another instance keeps its own copy of the jar
+        let other = instance._dir.path().join("other");
+        tokio::fs::create_dir_all(other.join(".prisma"))
+            .await
+            .unwrap();
+        tokio::fs::write(other.join("good.jar"), "good")
+            .await
+            .unwrap();
+        let mut copy = store.clone();
+        copy.inner[0].path = "./good.jar".to_string();
+        let copy_path = other.join(STORE_PATH);
+        copy.save(&copy_path.to_string_lossy()).await.unwrap();
+        SharedStore::global()
+            .register(&copy_path.to_string_lossy())
+            .await
+            .unwrap();
+
+        // the only copy of the shared store is corrupted
+        tokio::fs::remove_file(&store.inner[0].path).await.unwrap();
+        tokio::fs::write(&store.inner[0].path, "bad").await.unwrap();
+
+        Client::set_global(
+            Client::new(&NetworkOptions::default())
+                .unwrap()
+                .with_offline(true),
+        );
+        assert!(store.repair().await.unwrap().is_empty());
+        assert_eq!(
+            tokio::fs::read_to_string("./plugins/good.jar")
+                .await
+                .unwrap(),
+            "good"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod lock {
+    use prisma_core::{
+        extension::{ExtensionProvider, ExtensionType},
+        item::Item,
+        platform::Platform,
+        provider::Provider,
+        version::Version,
+    };
+    use prisma_hash::HashType;
+    use prisma_store::{
+        lock::{Lock, LockEntry},
+        StoreError,
+    };
+
+    fn version(game_version: &str, build: &str) -> Version {
+        Version {
+            game_version: Some(game_version.to_string()),
+            version_build: Some(build.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn entry(body: &[u8]) -> LockEntry {
+        LockEntry {
+            provider: Provider::Core(Platform::Paper),
+            version: version("1.20.1", "17"),
+            url: "https://example.com/paper-1.20.1-17.jar".to_string(),
+            hashes: vec![
+                HashType::new_sha1(HashType::compute_sha1(body)),
+                HashType::new_sha256(HashType::compute_sha256(body)),
+            ],
+            base: None,
+            launch: None,
+            build: None,
+        }
+    }
+
+    #[test]
+    fn check() {
+        let lock = Lock {
+            entries: vec![entry(b"jar")],
+        };
+        let pinned = |version| Item::new_core(Platform::Paper).with_version(version);
+
+        assert!(lock.check(&[pinned(Version::default())]).is_ok());
+        assert!(lock.check(&[pinned(version("1.20.1", "17"))]).is_ok());
+        assert!(matches!(
+            lock.check(&[pinned(version("1.20.1", "18"))]),
+            Err(StoreError::LockOutdated { item }) if item == "core Paper"
+        ));
+        assert!(matches!(
+            lock.check(&[Item::new_core(Platform::Purpur)]),
+            Err(StoreError::LockOutdated { .. })
+        ));
+
+        let meta = lock.entries[0].meta();
+        assert_eq!(meta.download_link, lock.entries[0].url);
+        assert_eq!(meta.hash, lock.entries[0].hashes[0]);
+        assert_eq!(meta.version_build.as_deref(), Some("17"));
+    }
+
+    #[tokio::test]
+    async fn verify_and_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("paper.jar");
+        tokio::fs::write(&jar, b"jar").await.unwrap();
+        let jar = jar.to_string_lossy().to_string();
+
+        assert!(entry(b"jar").verify(&jar).await);
+        assert!(!entry(b"other jar").verify(&jar).await);
+
+        let lock = Lock {
+            entries: vec![entry(b"jar")],
+        };
+        let path = dir.path().join("prisma.lock").to_string_lossy().to_string();
+        lock.save(&path).await.unwrap();
+        assert_eq!(Lock::load(&path).await.unwrap(), lock);
+    }
+
+    #[test]
+    fn remove_extension() {
+        let extension = |kind| LockEntry {
+            provider: Provider::Extension(("sodium".to_string(), Platform::Fabric, kind)),
+            ..entry(b"jar")
+        };
+        let plugin = extension(ExtensionType::Plugin(ExtensionProvider::Modrinth));
+        let r#mod = extension(ExtensionType::Mod(ExtensionProvider::Modrinth));
+        let mut lock = Lock {
+            entries: vec![plugin.clone(), r#mod.clone()],
+        };
+
+        let kind = ExtensionType::Mod(ExtensionProvider::Custom("mirror".to_string()));
+        assert_eq!(lock.remove_extension("sodium", &kind), Some(r#mod));
+        assert_eq!(lock.entries, [plugin]);
+        assert_eq!(lock.remove_extension("sodium", &kind), None);
+    }
+}
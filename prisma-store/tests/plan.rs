@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod plan {
+    use prisma_core::version::Version;
+    use prisma_hash::HashType;
+    use prisma_store::plan::{Action, Resolved};
+
+    fn resolved(game_version: &str, build: &str) -> Resolved {
+        Resolved {
+            game_version: Some(game_version.to_string()),
+            version_build: Some(build.to_string()),
+            loader_version: None,
+            installer_version: None,
+            url: format!("https://example.com/{}/{}", game_version, build),
+            hash: HashType::None,
+            launch: None,
+            build: None,
+            base: None,
+        }
+    }
+
+    #[test]
+    fn action_between() {
+        assert_eq!(
+            Action::between(&resolved("1.20.1", "17"), &resolved("1.20.1", "18")),
+            Action::Upgrade
+        );
+        assert_eq!(
+            Action::between(&resolved("1.20.1", "17"), &resolved("1.20.4", "1")),
+            Action::Upgrade
+        );
+        assert_eq!(
+            Action::between(&resolved("1.20.4", "1"), &resolved("1.20.1", "196")),
+            Action::Downgrade
+        );
+        assert_eq!(
+            Action::between(
+                &resolved("1.20.1", "4GyXKCLd"),
+                &resolved("1.20.1", "AANobbMI")
+            ),
+            Action::Upgrade
+        );
+    }
+
+    #[test]
+    fn satisfies() {
+        let installed = resolved("1.20.1", "17");
+        assert!(installed.satisfies(&Version::default()));
+        assert!(installed.satisfies(&Version {
+            game_version: Some("1.20.1".to_string()),
+            version_build: Some("17".to_string()),
+            ..Default::default()
+        }));
+        assert!(!installed.satisfies(&Version {
+            game_version: Some("1.20.1".to_string()),
+            version_build: Some("18".to_string()),
+            ..Default::default()
+        }));
+    }
+}
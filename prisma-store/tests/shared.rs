@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod shared {
+    use std::{path::Path, time::Duration};
+
+    use prisma_core::{item::Item, platform::Platform};
+    use prisma_hash::HashType;
+    use prisma_store::{
+        shared::SharedStore,
+        store::{Store, StoreItem},
+    };
+
+    async fn download(dir: &Path, body: &[u8]) -> std::path::PathBuf {
+        let file = tempfile::NamedTempFile::new_in(dir)
+            .unwrap()
+            .into_temp_path();
+        tokio::fs::write(&file, body).await.unwrap();
+        file.keep().unwrap()
+    }
+
+    fn store_item(path: &Path) -> StoreItem {
+        StoreItem {
+            item: Item::new_core(Platform::Paper),
+            hash: HashType::None,
+            path: path.to_string_lossy().to_string(),
+            symbol_link: "./paper.jar".to_string(),
+            url: "https://example.com/paper.jar".to_string(),
+            launch: None,
+            build: None,
+            base: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn add_and_collect_garbage() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = SharedStore::new(dir.path().join("store")).with_grace(Duration::ZERO);
+
+        // one copy of the same content
+        let (paper, hash) = shared
+            .add(&download(dir.path(), b"paper").await, "paper.jar")
+            .await
+            .unwrap();
+        let (again, _) = shared
+            .add(&download(dir.path(), b"paper").await, "paper.jar")
+            .await
+            .unwrap();
+        assert_eq!(paper, again);
+        assert_eq!(
+            hash,
+            HashType::new_sha256(HashType::compute_sha256("paper"))
+        );
+        assert!(paper.ends_with(format!("{}-paper.jar", HashType::compute_sha256("paper"))));
+        let (plugin, _) = shared
+            .add(&download(dir.path(), b"plugin").await, "plugin.jar")
+            .await
+            .unwrap();
+
+        // the first instance uses paper, the second one is gone
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        for (instance, path) in [(&first, &paper), (&second, &plugin)] {
+            tokio::fs::create_dir_all(instance).await.unwrap();
+            let store_path = instance.join("store.ron").to_string_lossy().to_string();
+            Store {
+                inner: vec![store_item(path)],
+            }
+            .save(&store_path)
+            .await
+            .unwrap();
+            shared.register(&store_path).await.unwrap();
+        }
+        tokio::fs::remove_dir_all(&second).await.unwrap();
+
+        assert_eq!(shared.collect_garbage().await.unwrap(), [plugin.as_path()]);
+        assert!(paper.exists());
+        assert!(!plugin.exists());
+        assert_eq!(
+            shared.find("paper.jar").await.as_deref(),
+            Some(paper.as_path())
+        );
+    }
+}